@@ -2,7 +2,15 @@ pub mod ast;
 mod token_type;
 mod token;
 mod literal;
+mod span;
+mod diagnostic;
+mod bigint;
 
 pub use token_type::TokenType;
 pub use token::Token;
 pub use literal::Literal;
+pub use span::Span;
+pub use diagnostic::Diagnostic;
+pub use bigint::BigInt;
+#[cfg(test)]
+mod tests;
@@ -1,5 +1,5 @@
 mod statement;
 mod expression;
 
-pub use statement::Statement;
+pub use statement::{CatchClause, Statement, SwitchCase};
 pub use expression::Expression;
\ No newline at end of file
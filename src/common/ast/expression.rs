@@ -1,43 +1,113 @@
-use crate::common::{TokenType, Literal};
+use crate::common::{TokenType, Literal, Span};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
-    Literal(Literal),
-    Identifier(String),
+    Literal {
+        value: Literal,
+        span: Span,
+    },
+    Identifier {
+        name: String,
+        span: Span,
+    },
     Grouping {
         expression: Box<Expression>,
+        span: Span,
     },
     Comparison {
         left: Box<Expression>,
         operator: TokenType,
         right: Box<Expression>,
+        span: Span,
     },
     Unary {
         operator: TokenType,
         right: Box<Expression>,
+        span: Span,
+    },
+    // `++x` or `x--`, `operator` is `++` or `--`
+    Update {
+        operator: TokenType,
+        prefix: bool,
+        target: Box<Expression>,
+        span: Span,
     },
     Binary {
         left: Box<Expression>,
         operator: TokenType,
         right: Box<Expression>,
+        span: Span,
+    },
+    // `&&`, `||` and `??`, which don't always evaluate `right`
+    Logical {
+        left: Box<Expression>,
+        operator: TokenType,
+        right: Box<Expression>,
+        span: Span,
+    },
+    Conditional {
+        test: Box<Expression>,
+        consequent: Box<Expression>,
+        alternate: Box<Expression>,
+        span: Span,
     },
+    // `a, b, c`, with at least two expressions
+    Sequence {
+        expressions: Vec<Expression>,
+        span: Span,
+    },
+    // `operator` is `=`, a compound assignment like `+=` or a logical one like `&&=`
+    Assignment {
+        target: Box<Expression>,
+        operator: TokenType,
+        value: Box<Expression>,
+        span: Span,
+    },
+    // `object.property`
+    Member {
+        object: Box<Expression>,
+        property: String,
+        span: Span,
+    },
+    // `name(args)` or a method call, `object.name(args)`
     Call {
-        callee: String,
-        args: Box<Vec<Expression>>
+        callee: Box<Expression>,
+        args: Vec<Expression>,
+        span: Span,
+    },
+    // `quasis` are the text parts around the substitutions, there is always one more of them
+    Template {
+        quasis: Vec<String>,
+        expressions: Vec<Expression>,
+        span: Span,
     },
 }
 
 impl Expression {
     pub fn extract_string(expr: &Self) -> Option<String> {
         match expr {
-            Self::Literal(literal) => {
-                match literal {
-                    Literal::String(value) => Some(String::from(value)),
-                    _ => None,
-                }
-            },
-            Self::Identifier(value) => Some(String::from(value)),
+            Self::Literal { value: Literal::String(value), .. } => Some(String::from(value)),
+            Self::Identifier { name, .. } => Some(String::from(name)),
             _ => None,
         }
     }
-}
\ No newline at end of file
+
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Literal { span, .. }
+            | Self::Identifier { span, .. }
+            | Self::Grouping { span, .. }
+            | Self::Comparison { span, .. }
+            | Self::Unary { span, .. }
+            | Self::Update { span, .. }
+            | Self::Binary { span, .. }
+            | Self::Logical { span, .. }
+            | Self::Conditional { span, .. }
+            | Self::Sequence { span, .. }
+            | Self::Assignment { span, .. }
+            | Self::Member { span, .. }
+            | Self::Call { span, .. }
+            | Self::Template { span, .. } => *span,
+        }
+    }
+}
@@ -1,21 +1,136 @@
 use super::expression::Expression;
+use crate::common::Span;
 
 #[derive(Debug, PartialEq, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Statement {
   ExpressionStatement {
     expression: Box<Expression>,
+    span: Span,
   },
   Declaration {
     is_const: bool,
     name: String,
     value: Box<Option<Expression>>,
+    span: Span,
   },
   FunctionDeclaration {
     name: String,
     params: Vec<String>,
-    body: Box<Vec<Statement>>,
+    body: Vec<Statement>,
+    span: Span,
   },
   Return {
     expression: Box<Expression>,
+    span: Span,
+  },
+  If {
+    test: Box<Expression>,
+    consequent: Box<Statement>,
+    alternate: Option<Box<Statement>>,
+    span: Span,
+  },
+  Block {
+    body: Vec<Statement>,
+    span: Span,
+  },
+  While {
+    test: Box<Expression>,
+    body: Box<Statement>,
+    span: Span,
+  },
+  DoWhile {
+    body: Box<Statement>,
+    test: Box<Expression>,
+    span: Span,
+  },
+  For {
+    init: Option<Box<Statement>>,
+    test: Option<Box<Expression>>,
+    update: Option<Box<Expression>>,
+    body: Box<Statement>,
+    span: Span,
+  },
+  // `left` is a declaration without initializer or an expression statement of an identifier
+  ForIn {
+    left: Box<Statement>,
+    right: Box<Expression>,
+    body: Box<Statement>,
+    span: Span,
+  },
+  ForOf {
+    left: Box<Statement>,
+    right: Box<Expression>,
+    body: Box<Statement>,
+    span: Span,
+  },
+  Switch {
+    discriminant: Box<Expression>,
+    cases: Vec<SwitchCase>,
+    span: Span,
+  },
+  Break {
+    label: Option<String>,
+    span: Span,
+  },
+  Continue {
+    label: Option<String>,
+    span: Span,
+  },
+  Throw {
+    expression: Box<Expression>,
+    span: Span,
+  },
+  // `block` and `finalizer` are `Statement::Block`s, at least one of `handler` and `finalizer` is there
+  Try {
+    block: Box<Statement>,
+    handler: Option<CatchClause>,
+    finalizer: Option<Box<Statement>>,
+    span: Span,
+  },
+  Labeled {
+    label: String,
+    body: Box<Statement>,
+    span: Span,
+  },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SwitchCase {
+  // `None` for the `default` clause
+  pub test: Option<Expression>,
+  pub consequent: Vec<Statement>,
+  pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CatchClause {
+  // `None` for `catch { ... }` without a binding
+  pub param: Option<String>,
+  pub body: Box<Statement>,
+  pub span: Span,
+}
+
+impl Statement {
+  pub fn span(&self) -> Span {
+    match self {
+      Self::ExpressionStatement { span, .. }
+      | Self::Declaration { span, .. }
+      | Self::FunctionDeclaration { span, .. }
+      | Self::Return { span, .. }
+      | Self::If { span, .. }
+      | Self::Block { span, .. }
+      | Self::While { span, .. }
+      | Self::DoWhile { span, .. }
+      | Self::For { span, .. }
+      | Self::ForIn { span, .. }
+      | Self::ForOf { span, .. }
+      | Self::Switch { span, .. }
+      | Self::Break { span, .. }
+      | Self::Continue { span, .. }
+      | Self::Throw { span, .. }
+      | Self::Try { span, .. }
+      | Self::Labeled { span, .. } => *span,
+    }
   }
 }
@@ -0,0 +1,491 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Arbitrary-precision integer backing JS BigInt values.
+///
+/// Stored as a sign and a magnitude of little-endian base 2^32 limbs without
+/// trailing zero limbs, so zero is an empty magnitude and is never negative.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn from_u64(value: u64) -> Self {
+        Self {
+            negative: false,
+            magnitude: vec![value as u32, (value >> 32) as u32],
+        }
+        .normalize()
+    }
+
+    /// Exact conversion of an integral f64, `None` for NaN, infinities and fractions.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() || value.fract() != 0.0 {
+            return None;
+        }
+
+        let bits = value.abs().to_bits();
+        let exponent = ((bits >> 52) & 0x7FF) as i64;
+        if exponent == 0 {
+            // only zero is integral among subnormals
+            return Some(Self::zero());
+        }
+
+        let mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        let shift = exponent - 1075;
+        let mut result = if shift >= 0 {
+            Self::from_u64(mantissa).shl_magnitude(shift as usize)
+        } else {
+            Self::from_u64(mantissa >> (-shift))
+        };
+        result.negative = value < 0.0;
+
+        Some(result.normalize())
+    }
+
+    /// Parses digits of `radix` (2 to 36) without sign or prefix.
+    pub fn parse(digits: &str, radix: u32) -> Option<Self> {
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut result = Self::zero();
+        for c in digits.chars() {
+            let digit = c.to_digit(radix)?;
+            result.mul_add_small(radix, digit);
+        }
+
+        Some(result.normalize())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            negative: !self.negative,
+            magnitude: self.magnitude.clone(),
+        }
+        .normalize()
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Self {
+                negative: self.negative,
+                magnitude: add_magnitudes(&self.magnitude, &other.magnitude),
+            }
+            .normalize();
+        }
+
+        match compare_magnitudes(&self.magnitude, &other.magnitude) {
+            Ordering::Less => Self {
+                negative: other.negative,
+                magnitude: sub_magnitudes(&other.magnitude, &self.magnitude),
+            },
+            _ => Self {
+                negative: self.negative,
+                magnitude: sub_magnitudes(&self.magnitude, &other.magnitude),
+            },
+        }
+        .normalize()
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut magnitude = vec![0u32; self.magnitude.len() + other.magnitude.len()];
+        for (i, &a) in self.magnitude.iter().enumerate() {
+            let mut carry: u64 = 0;
+            for (j, &b) in other.magnitude.iter().enumerate() {
+                let current = magnitude[i + j] as u64 + a as u64 * b as u64 + carry;
+                magnitude[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            magnitude[i + other.magnitude.len()] = carry as u32;
+        }
+
+        Self {
+            negative: self.negative != other.negative,
+            magnitude,
+        }
+        .normalize()
+    }
+
+    /// Quotient truncated toward zero and the remainder with the dividend's sign,
+    /// `None` when dividing by zero.
+    pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem_magnitudes(&self.magnitude, &other.magnitude);
+        let quotient = Self {
+            negative: self.negative != other.negative,
+            magnitude: quotient,
+        };
+        let remainder = Self {
+            negative: self.negative,
+            magnitude: remainder,
+        };
+
+        Some((quotient.normalize(), remainder.normalize()))
+    }
+
+    /// `None` when the result would be larger than the bits a BigInt may have.
+    /// Expects a non-negative exponent.
+    pub fn pow(&self, exponent: &Self) -> Option<Self> {
+        let one = Self::from_u64(1);
+        if exponent.is_zero() || *self == one {
+            return Some(one);
+        }
+        if self.is_zero() {
+            return Some(Self::zero());
+        }
+        if self.magnitude == one.magnitude {
+            // -1 alternates with the parity of the exponent
+            return Some(if exponent.bit(0) { self.clone() } else { one });
+        }
+
+        let mut exponent = exponent.to_u64().filter(|&e| (self.bit_length() as u64).saturating_mul(e) <= MAX_BITS)?;
+        let (mut result, mut base) = (one, self.clone());
+        // square-and-multiply over the bits of the exponent
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base);
+            }
+        }
+
+        Some(result)
+    }
+
+    /// `self * 2^shift`, a negative shift divides rounding toward negative infinity.
+    /// `None` when the result would be too large.
+    pub fn shl(&self, shift: &Self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::zero());
+        }
+
+        if !shift.negative {
+            let amount = shift.to_u64().filter(|&amount| self.bit_length() as u64 + amount <= MAX_BITS)?;
+            return Some(self.shl_magnitude(amount as usize));
+        }
+
+        // floor(-a / 2^n) is -(ceil(a / 2^n)), or -((a - 1) / 2^n) - 1 for truncating division
+        let amount = shift.neg().to_u64().unwrap_or(u64::MAX).min(self.bit_length() as u64) as usize;
+        if !self.negative {
+            return Some(self.shr_magnitude(amount));
+        }
+        let one = Self::from_u64(1);
+        Some(self.neg().sub(&one).shr_magnitude(amount).add(&one).neg())
+    }
+
+    pub fn shr(&self, shift: &Self) -> Option<Self> {
+        self.shl(&shift.neg())
+    }
+
+    pub fn bitand(&self, other: &Self) -> Self {
+        self.bitwise(other, |a, b| a & b)
+    }
+
+    pub fn bitor(&self, other: &Self) -> Self {
+        self.bitwise(other, |a, b| a | b)
+    }
+
+    pub fn bitxor(&self, other: &Self) -> Self {
+        self.bitwise(other, |a, b| a ^ b)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Nearest f64, rounding half to even.
+    pub fn to_f64(&self) -> f64 {
+        let bit_length = self.bit_length();
+        if bit_length == 0 {
+            return 0.0;
+        }
+
+        // keep the top 64 bits and fold everything below into a sticky bit
+        let shift = bit_length.saturating_sub(64);
+        let mut top: u64 = 0;
+        for i in (shift..bit_length).rev() {
+            top = (top << 1) | self.bit(i) as u64;
+        }
+        let sticky = (0..shift).any(|i| self.bit(i));
+
+        let value = if sticky && shift > 0 {
+            // the sticky bit is far below f64 precision, it only breaks ties
+            ((top as u128) << 1 | 1) as f64 * 2f64.powi(shift as i32 - 1)
+        } else {
+            top as f64 * 2f64.powi(shift as i32)
+        };
+
+        if self.negative { -value } else { value }
+    }
+
+    /// Compares with a Number by mathematical value, `None` when `other` is NaN.
+    pub fn partial_cmp_f64(&self, other: f64) -> Option<Ordering> {
+        if other.is_nan() {
+            return None;
+        }
+        if other.is_infinite() {
+            return Some(if other > 0.0 { Ordering::Less } else { Ordering::Greater });
+        }
+
+        let floor = other.floor();
+        let floor_bigint = Self::from_f64(floor)?;
+        match self.cmp(&floor_bigint) {
+            // self == floor(other) < other when other has a fraction
+            Ordering::Equal if floor != other => Some(Ordering::Less),
+            ordering => Some(ordering),
+        }
+    }
+
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        if self.is_zero() {
+            return String::from("0");
+        }
+
+        let mut digits = vec![];
+        let mut magnitude = self.magnitude.clone();
+        while !magnitude.is_empty() {
+            let remainder = div_small_in_place(&mut magnitude, radix);
+            digits.push(std::char::from_digit(remainder, radix).unwrap_or('?'));
+        }
+
+        if self.negative {
+            digits.push('-');
+        }
+        digits.iter().rev().collect()
+    }
+
+    /// Applies `operation` to the infinite two's complement representations.
+    fn bitwise(&self, other: &Self, operation: fn(u32, u32) -> u32) -> Self {
+        // one more limb than needed holds the sign
+        let len = self.magnitude.len().max(other.magnitude.len()) + 1;
+        let (a, b) = (self.twos_complement(len), other.twos_complement(len));
+        let limbs: Vec<u32> = a.iter().zip(b.iter()).map(|(&a, &b)| operation(a, b)).collect();
+
+        if limbs[len - 1] >> 31 == 0 {
+            return Self { negative: false, magnitude: limbs }.normalize();
+        }
+        let inverted = Self { negative: false, magnitude: limbs.iter().map(|limb| !limb).collect() }.normalize();
+        inverted.add(&Self::from_u64(1)).neg()
+    }
+
+    fn twos_complement(&self, len: usize) -> Vec<u32> {
+        if !self.negative {
+            let mut limbs = self.magnitude.clone();
+            limbs.resize(len, 0);
+            return limbs;
+        }
+
+        // -a is !(a - 1)
+        let mut limbs = self.neg().sub(&Self::from_u64(1)).magnitude;
+        limbs.resize(len, 0);
+        limbs.iter().map(|limb| !limb).collect()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        if self.negative || self.magnitude.len() > 2 {
+            return None;
+        }
+        Some(self.magnitude.iter().rev().fold(0, |value, &limb| (value << 32) | limb as u64))
+    }
+
+    fn shr_magnitude(&self, shift: usize) -> Self {
+        let (limbs, bits) = (shift / 32, shift % 32);
+        let magnitude = self.magnitude.get(limbs..).unwrap_or(&[]);
+        let shifted = (0..magnitude.len())
+            .map(|i| {
+                let high = if bits == 0 { 0 } else { magnitude.get(i + 1).map_or(0, |&limb| limb << (32 - bits)) };
+                (magnitude[i] >> bits) | high
+            })
+            .collect();
+
+        Self {
+            negative: self.negative,
+            magnitude: shifted,
+        }
+        .normalize()
+    }
+
+    fn normalize(mut self) -> Self {
+        while self.magnitude.last() == Some(&0) {
+            self.magnitude.pop();
+        }
+        if self.magnitude.is_empty() {
+            self.negative = false;
+        }
+        self
+    }
+
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in self.magnitude.iter_mut() {
+            let current = *limb as u64 * factor as u64 + carry;
+            *limb = current as u32;
+            carry = current >> 32;
+        }
+        if carry > 0 {
+            self.magnitude.push(carry as u32);
+        }
+    }
+
+    fn shl_magnitude(&self, shift: usize) -> Self {
+        let (limbs, bits) = (shift / 32, shift % 32);
+        let mut magnitude = vec![0u32; limbs];
+        let mut carry = 0u32;
+        for &limb in self.magnitude.iter() {
+            if bits == 0 {
+                magnitude.push(limb);
+            } else {
+                magnitude.push((limb << bits) | carry);
+                carry = limb >> (32 - bits);
+            }
+        }
+        if carry > 0 {
+            magnitude.push(carry);
+        }
+
+        Self {
+            negative: self.negative,
+            magnitude,
+        }
+        .normalize()
+    }
+
+    fn bit_length(&self) -> usize {
+        match self.magnitude.last() {
+            Some(top) => self.magnitude.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self.magnitude
+            .get(index / 32)
+            .is_some_and(|limb| (limb >> (index % 32)) & 1 == 1)
+    }
+}
+
+// the most bits a result may have, like the limit engines use
+const MAX_BITS: u64 = 1 << 30;
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitudes(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string_radix(10))
+    }
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let current = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(current as u32);
+        carry = current >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+// expects a >= b
+fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut current = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if current < 0 {
+            current += 1 << 32;
+            borrow = 1;
+        }
+        result.push(current as u32);
+    }
+    result
+}
+
+fn div_small_in_place(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0u64;
+    for limb in magnitude.iter_mut().rev() {
+        let current = (remainder << 32) | *limb as u64;
+        *limb = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    remainder as u32
+}
+
+fn div_rem_magnitudes(dividend: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if divisor.len() == 1 {
+        let mut quotient = dividend.to_vec();
+        let remainder = div_small_in_place(&mut quotient, divisor[0]);
+        return (quotient, vec![remainder]);
+    }
+
+    // shift-subtract long division, one bit at a time
+    let dividend = BigInt { negative: false, magnitude: dividend.to_vec() }.normalize();
+    let mut quotient = vec![0u32; dividend.magnitude.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for i in (0..dividend.bit_length()).rev() {
+        remainder = BigInt { negative: false, magnitude: remainder }.shl_magnitude(1).magnitude;
+        if dividend.bit(i) {
+            match remainder.first_mut() {
+                Some(limb) => *limb |= 1,
+                None => remainder.push(1),
+            }
+        }
+
+        if compare_magnitudes(&remainder, divisor) != Ordering::Less {
+            remainder = BigInt { negative: false, magnitude: sub_magnitudes(&remainder, divisor) }
+                .normalize()
+                .magnitude;
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+
+    (quotient, remainder)
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use super::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    #[allow(dead_code)] // nothing reports warnings yet
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// An error or warning that points at a location in the source.
+///
+/// Codes are grouped by stage: `E00xx` tokenizer, `E01xx` parser, `E02xx` runtime.
+/// Every fallible stage returns one, so the details are boxed to keep their
+/// `Result`s small, and read through `Deref`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic(Box<Details>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Details {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Deref for Diagnostic {
+    type Target = Details;
+
+    fn deref(&self) -> &Details {
+        &self.0
+    }
+}
+
+impl DerefMut for Diagnostic {
+    fn deref_mut(&mut self) -> &mut Details {
+        &mut self.0
+    }
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self(Box::new(Details {
+            severity,
+            code,
+            message: message.into(),
+            span,
+            labels: vec![],
+            notes: vec![],
+        }))
+    }
+
+    pub fn error(code: &'static str, message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, code, message, span)
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label { span, message: message.into() });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Renders the diagnostic the way rustc does:
+    ///
+    /// ```text
+    /// error[E0101]: expected ';' after declaration
+    ///  --> main.js:1:10
+    ///   |
+    /// 1 | let x = 5
+    ///   |          ^
+    /// ```
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let mut annotations: Vec<(Span, char, &str)> = vec![(self.span, '^', "")];
+        for label in self.labels.iter() {
+            annotations.push((label.span, '-', label.message.as_str()));
+        }
+        annotations.sort_by_key(|(span, ..)| (span.line, span.column));
+
+        let max_line = annotations.iter().map(|(span, ..)| span.line).max().unwrap_or(1);
+        let gutter = " ".repeat(max_line.to_string().len());
+
+        let mut output = format!("{}[{}]: {}\n", self.severity, self.code, self.message);
+        output += &format!("{}--> {}:{}:{}\n", gutter, file_name, self.span.line, self.span.column);
+        output += &format!("{} |\n", gutter);
+
+        let mut previous_line = None;
+        for (span, marker, message) in annotations.iter() {
+            let line_text = source_line(source, span.line);
+
+            if previous_line != Some(span.line) {
+                if previous_line.is_some_and(|line| line + 1 < span.line) {
+                    output += "...\n";
+                }
+                output += &format!("{:>width$} | {}\n", span.line, line_text, width = gutter.len());
+                previous_line = Some(span.line);
+            }
+
+            // underline up to the end of the first line of the span, at least one char wide
+            let available = line_text.chars().count().saturating_sub(span.column.saturating_sub(1));
+            let length = source
+                .get(span.start..span.end)
+                .map(|text| text.split(is_line_break).next().unwrap_or("").chars().count())
+                .unwrap_or(0)
+                .min(available)
+                .max(1);
+
+            let underline = format!(
+                "{}{}",
+                " ".repeat(span.column.saturating_sub(1)),
+                marker.to_string().repeat(length)
+            );
+            if message.is_empty() {
+                output += &format!("{} | {}\n", gutter, underline);
+            } else {
+                output += &format!("{} | {} {}\n", gutter, underline, message);
+            }
+        }
+
+        for note in self.notes.iter() {
+            output += &format!("{} = note: {}\n", gutter, note);
+        }
+
+        output
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
+
+/// The text of a line, counted the way the tokenizer does: `\r\n` is one line
+/// break, and so are a lone `\r`, U+2028 and U+2029.
+fn source_line(source: &str, line: usize) -> &str {
+    let mut rest = source;
+    for _ in 1..line {
+        let Some(index) = rest.find(is_line_break) else {
+            return "";
+        };
+        let rest_of_line = &rest[index..];
+        let length = if rest_of_line.starts_with("\r\n") { 2 } else { rest_of_line.chars().next().map_or(1, char::len_utf8) };
+        rest = &rest_of_line[length..];
+    }
+    rest.split(is_line_break).next().unwrap_or("")
+}
+
+fn is_line_break(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
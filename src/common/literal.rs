@@ -1,6 +1,9 @@
+use super::bigint::BigInt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     Number(f64),
+    BigInt(BigInt),
     String(String),
     Boolean(bool),
     Null,
@@ -0,0 +1,27 @@
+/// Location of a piece of source code.
+///
+/// `start` and `end` are byte offsets into the source (`end` is exclusive),
+/// `line` and `column` are 1-based and point at `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Self { start, end, line, column }
+    }
+
+    /// Span that starts at `self` and ends at `other`.
+    pub fn merge(&self, other: Span) -> Self {
+        Self {
+            start: self.start,
+            end: other.end.max(self.end),
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
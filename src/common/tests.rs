@@ -0,0 +1,174 @@
+use super::*;
+
+#[test]
+fn test_render_primary_span() {
+    let source = "let x = 5;\nlet y = x +;\n";
+    let diagnostic = Diagnostic::error(
+        "E0102",
+        "Expected factor (number, '(', unary -) but got Semicolon",
+        Span::new(22, 23, 2, 12),
+    );
+
+    assert_eq!(
+        diagnostic.render("main.js", source),
+        [
+            "error[E0102]: Expected factor (number, '(', unary -) but got Semicolon",
+            " --> main.js:2:12",
+            "  |",
+            "2 | let y = x +;",
+            "  |            ^",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_render_labels_and_notes() {
+    let source = "let a = 1;\nlet b = a + 'x';";
+    let diagnostic = Diagnostic::error("E0201", "unhandled binary expression", Span::new(19, 26, 2, 9))
+        .with_label(Span::new(23, 26, 2, 13), "this is string")
+        .with_label(Span::new(4, 5, 1, 5), "declared here")
+        .with_note("only numbers can be added");
+
+    assert_eq!(
+        diagnostic.render("main.js", source),
+        [
+            "error[E0201]: unhandled binary expression",
+            " --> main.js:2:9",
+            "  |",
+            "1 | let a = 1;",
+            "  |     - declared here",
+            "2 | let b = a + 'x';",
+            "  |         ^^^^^^^",
+            "  |             --- this is string",
+            "  = note: only numbers can be added",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_render_span_at_end_of_file() {
+    let source = "let x = 1";
+    let diagnostic = Diagnostic::error("E0101", "expected ';' after declaration", Span::new(9, 9, 1, 10));
+
+    assert_eq!(
+        diagnostic.render("main.js", source),
+        [
+            "error[E0101]: expected ';' after declaration",
+            " --> main.js:1:10",
+            "  |",
+            "1 | let x = 1",
+            "  |          ^",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_render_line_after_other_line_breaks() {
+    let source = "let a = 1;\rlet b = 2;\r\nlet c = 3;\u{2028}let d = ;";
+    let diagnostic = Diagnostic::error("E0102", "expected expression", Span::new(44, 45, 4, 9));
+
+    assert_eq!(
+        diagnostic.render("main.js", source),
+        [
+            "error[E0102]: expected expression",
+            " --> main.js:4:9",
+            "  |",
+            "4 | let d = ;",
+            "  |         ^",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_display() {
+    let diagnostic = Diagnostic::error("E0001", "unterminated block comment", Span::default());
+    assert_eq!(diagnostic.to_string(), "error[E0001]: unterminated block comment");
+}
+
+fn bigint(value: &str) -> BigInt {
+    match value.strip_prefix('-') {
+        Some(digits) => BigInt::parse(digits, 10).unwrap().neg(),
+        None => BigInt::parse(value, 10).unwrap(),
+    }
+}
+
+#[test]
+fn test_bigint_arithmetic() {
+    let a = bigint("123456789012345678901234567890");
+    let b = bigint("987654321098765432109876543210");
+
+    assert_eq!(a.add(&b).to_string(), "1111111110111111111011111111100");
+    assert_eq!(a.sub(&b).to_string(), "-864197532086419753208641975320");
+    assert_eq!(a.mul(&b).to_string(), "121932631137021795226185032733622923332237463801111263526900");
+    assert_eq!(bigint("-5").add(&bigint("5")), BigInt::zero());
+    assert_eq!(bigint("0").neg().to_string(), "0");
+}
+
+#[test]
+fn test_bigint_division_truncates_toward_zero() {
+    let div_rem = |a: &str, b: &str| {
+        let (quotient, remainder) = bigint(a).div_rem(&bigint(b)).unwrap();
+        (quotient.to_string(), remainder.to_string())
+    };
+
+    assert_eq!(div_rem("7", "2"), ("3".into(), "1".into()));
+    assert_eq!(div_rem("-7", "2"), ("-3".into(), "-1".into()));
+    assert_eq!(div_rem("7", "-2"), ("-3".into(), "1".into()));
+    assert_eq!(
+        div_rem("121932631137021795226185032733622923332237463801111263526901", "987654321098765432109876543210"),
+        ("123456789012345678901234567890".into(), "1".into())
+    );
+    assert_eq!(bigint("1").div_rem(&BigInt::zero()), None);
+}
+
+#[test]
+fn test_bigint_pow() {
+    assert_eq!(bigint("3").pow(&bigint("40")).unwrap().to_string(), "12157665459056928801");
+    assert_eq!(bigint("-3").pow(&bigint("3")).unwrap().to_string(), "-27");
+    assert_eq!(bigint("0").pow(&bigint("0")).unwrap().to_string(), "1");
+    assert_eq!(bigint("-1").pow(&bigint("100000000001")).unwrap().to_string(), "-1");
+    assert_eq!(bigint("2").pow(&bigint("10000000000")), None);
+}
+
+#[test]
+fn test_bigint_bitwise_uses_twos_complement() {
+    assert_eq!(bigint("-6").bitand(&bigint("3")).to_string(), "2");
+    assert_eq!(bigint("-6").bitor(&bigint("3")).to_string(), "-5");
+    assert_eq!(bigint("-6").bitxor(&bigint("-3")).to_string(), "7");
+    assert_eq!(bigint("-1").bitand(&bigint("18446744073709551616")).to_string(), "18446744073709551616");
+    assert_eq!(bigint("1").shl(&bigint("64")).unwrap().to_string(), "18446744073709551616");
+    assert_eq!(bigint("-5").shr(&bigint("1")).unwrap().to_string(), "-3");
+    assert_eq!(bigint("-1").shr(&bigint("100")).unwrap().to_string(), "-1");
+    assert_eq!(bigint("5").shl(&bigint("-1")).unwrap().to_string(), "2");
+}
+
+#[test]
+fn test_bigint_number_conversions() {
+    assert_eq!(BigInt::from_f64(2f64.powi(64)).unwrap().to_string(), "18446744073709551616");
+    assert_eq!(BigInt::from_f64(-42.0).unwrap().to_string(), "-42");
+    assert_eq!(BigInt::from_f64(-0.0), Some(BigInt::zero()));
+    assert_eq!(BigInt::from_f64(1.5), None);
+    assert_eq!(BigInt::from_f64(f64::NAN), None);
+    assert_eq!(bigint("255").to_string_radix(16), "ff");
+}
+
+#[test]
+fn test_bigint_compares_with_numbers() {
+    use std::cmp::Ordering;
+
+    assert_eq!(bigint("9007199254740993").partial_cmp_f64(9007199254740992.0), Some(Ordering::Greater));
+    assert_eq!(bigint("1").partial_cmp_f64(1.5), Some(Ordering::Less));
+    assert_eq!(bigint("-2").partial_cmp_f64(-1.5), Some(Ordering::Less));
+    assert_eq!(bigint("-1").partial_cmp_f64(-1.5), Some(Ordering::Greater));
+    assert_eq!(bigint("3").partial_cmp_f64(3.0), Some(Ordering::Equal));
+    assert_eq!(bigint("3").partial_cmp_f64(f64::INFINITY), Some(Ordering::Less));
+    assert_eq!(bigint("3").partial_cmp_f64(f64::NAN), None);
+}
@@ -1,19 +1,26 @@
 use std::fmt;
 use super::token_type::TokenType;
+use super::span::Span;
 
 pub struct Token {
     pub kind: TokenType,
-    pub line: usize,
+    pub span: Span,
+    // whether a line terminator comes between this token and the previous one
+    pub newline_before: bool,
 }
 
 impl Token {
-    pub fn new(kind: TokenType, line: usize) -> Self {
-        Self { kind, line }
+    pub fn new(kind: TokenType, span: Span) -> Self {
+        Self { kind, span, newline_before: false }
     }
 }
 
 impl fmt::Debug for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Token {{ kind: {:?}, line: {:?}}}", self.kind, self.line)
+        write!(
+            f,
+            "Token {{ kind: {:?}, line: {:?}, column: {:?}, bytes: {}..{} }}",
+            self.kind, self.span.line, self.span.column, self.span.start, self.span.end
+        )
     }
 }
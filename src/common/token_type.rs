@@ -6,12 +6,51 @@ pub enum TokenType {
     RightParen, // )
     LeftCurlyBrace, // {
     RightCurlyBrace, // }
+    LeftSquareBracket, // [
+    RightSquareBracket, // ]
     Plus,
     Minus,
     Star,
     Slash,
+    Percent,
+    StarStar, // **
+    Ampersand,
+    Pipe,
+    Caret,
+    LeftShift, // <<
+    RightShift, // >>
+    UnsignedRightShift, // >>>
     Assign,
     Comma,
+    Colon,
+    Dot,
+
+    // Logical Operators
+    AmpersandAmpersand, // &&
+    PipePipe, // ||
+    QuestionQuestion, // ??
+    Bang, // !
+    Question, // ?
+    Tilde, // ~
+    PlusPlus, // ++
+    MinusMinus, // --
+
+    // Compound Assignment Operators
+    PlusAssign,
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
+    PercentAssign,
+    StarStarAssign,
+    LeftShiftAssign,
+    RightShiftAssign,
+    UnsignedRightShiftAssign,
+    AmpersandAssign,
+    PipeAssign,
+    CaretAssign,
+    AmpersandAmpersandAssign,
+    PipePipeAssign,
+    QuestionQuestionAssign,
 
     // Comparison Operators
     Equal,
@@ -25,12 +64,36 @@ pub enum TokenType {
 
     Literal(Literal),
 
+    // Template literal parts, holding the cooked text between the delimiters
+    NoSubstitutionTemplate(String), // `text`
+    TemplateHead(String), // `text${
+    TemplateMiddle(String), // }text${
+    TemplateTail(String), // }text`
+
     // Binding Keywords
     KeywordLet,
     KeywordConst,
 
     Function,
     Return,
+    If,
+    Else,
+    While,
+    Do,
+    For,
+    Break,
+    Continue,
+    In,
+    Switch,
+    Case,
+    Default,
+    Throw,
+    Try,
+    Catch,
+    Finally,
+    Typeof,
+    Void,
+    Delete,
     Identifier(String),
     Unsupported(String),
     Semicolon,
@@ -38,19 +101,54 @@ pub enum TokenType {
 }
 
 impl TokenType {
-    pub fn is_comparison_operator(&self) -> bool {
+    pub fn is_equality_operator(&self) -> bool {
+        matches!(
+            self,
+            TokenType::Equal | TokenType::NotEqual | TokenType::StrictEqual | TokenType::StrictNotEqual
+        )
+    }
+
+    pub fn is_relational_operator(&self) -> bool {
         matches!(
             self,
-            TokenType::Equal
-                | TokenType::NotEqual
-                | TokenType::StrictEqual
-                | TokenType::StrictNotEqual
-                | TokenType::GreaterThan
-                | TokenType::GreaterThanOrEqual
-                | TokenType::LessThan
-                | TokenType::LessThanOrEqual
+            TokenType::GreaterThan | TokenType::GreaterThanOrEqual | TokenType::LessThan | TokenType::LessThanOrEqual
         )
     }
+
+    pub fn is_assignment_operator(&self) -> bool {
+        *self == TokenType::Assign
+            || self.compound_assignment_operator().is_some()
+            || self.logical_assignment_operator().is_some()
+    }
+
+    /// The binary operator a compound assignment applies, `+` for `+=`.
+    pub fn compound_assignment_operator(&self) -> Option<TokenType> {
+        match self {
+            TokenType::PlusAssign => Some(TokenType::Plus),
+            TokenType::MinusAssign => Some(TokenType::Minus),
+            TokenType::StarAssign => Some(TokenType::Star),
+            TokenType::SlashAssign => Some(TokenType::Slash),
+            TokenType::PercentAssign => Some(TokenType::Percent),
+            TokenType::StarStarAssign => Some(TokenType::StarStar),
+            TokenType::LeftShiftAssign => Some(TokenType::LeftShift),
+            TokenType::RightShiftAssign => Some(TokenType::RightShift),
+            TokenType::UnsignedRightShiftAssign => Some(TokenType::UnsignedRightShift),
+            TokenType::AmpersandAssign => Some(TokenType::Ampersand),
+            TokenType::PipeAssign => Some(TokenType::Pipe),
+            TokenType::CaretAssign => Some(TokenType::Caret),
+            _ => None,
+        }
+    }
+
+    /// The logical operator a logical assignment short-circuits with, `&&` for `&&=`.
+    pub fn logical_assignment_operator(&self) -> Option<TokenType> {
+        match self {
+            TokenType::AmpersandAmpersandAssign => Some(TokenType::AmpersandAmpersand),
+            TokenType::PipePipeAssign => Some(TokenType::PipePipe),
+            TokenType::QuestionQuestionAssign => Some(TokenType::QuestionQuestion),
+            _ => None,
+        }
+    }
 }
 
 impl From<char> for TokenType {
@@ -62,13 +160,24 @@ impl From<char> for TokenType {
             '-' => Self::Minus,
             '*' => Self::Star,
             '/' => Self::Slash,
+            '%' => Self::Percent,
+            '&' => Self::Ampersand,
+            '|' => Self::Pipe,
+            '^' => Self::Caret,
             '=' => Self::Assign,
             ';' => Self::Semicolon,
             '>' => Self::GreaterThan,
             '<' => Self::LessThan,
             '{' => Self::LeftCurlyBrace,
             '}' => Self::RightCurlyBrace,
+            '[' => Self::LeftSquareBracket,
+            ']' => Self::RightSquareBracket,
             ',' => Self::Comma,
+            ':' => Self::Colon,
+            '.' => Self::Dot,
+            '!' => Self::Bang,
+            '?' => Self::Question,
+            '~' => Self::Tilde,
             _ => Self::Unsupported(String::from(value))
         }
     }
@@ -89,13 +198,31 @@ impl From<&[char]> for TokenType {
             "undefined" => Self::Literal(Literal::Undefined),
             "function" => Self::Function,
             "return" => Self::Return,
+            "if" => Self::If,
+            "else" => Self::Else,
+            "while" => Self::While,
+            "do" => Self::Do,
+            "for" => Self::For,
+            "break" => Self::Break,
+            "continue" => Self::Continue,
+            "in" => Self::In,
+            "switch" => Self::Switch,
+            "case" => Self::Case,
+            "default" => Self::Default,
+            "throw" => Self::Throw,
+            "try" => Self::Try,
+            "catch" => Self::Catch,
+            "finally" => Self::Finally,
+            "typeof" => Self::Typeof,
+            "void" => Self::Void,
+            "delete" => Self::Delete,
             "==" => Self::Equal,
             "!=" => Self::NotEqual,
             "===" => Self::StrictEqual,
             "!==" => Self::StrictNotEqual,
             ">=" => Self::GreaterThanOrEqual,
             "<=" => Self::LessThanOrEqual,
-            _ => Self::Identifier(String::from(value)),
+            _ => Self::Identifier(value),
         }
     }
 }
@@ -1,45 +1,67 @@
+
 mod common;
 mod tokenizer;
 mod parser;
 mod runtime;
 
+use common::Diagnostic;
 use parser::Parser;
-use runtime::Runtime;
+use runtime::{Runtime, STACK_SIZE};
 use tokenizer::Tokenizer;
-use std::{env::{self, Args}, fs, io, path::Path};
+use std::{env::{self, Args}, fs, io, path::Path, process, thread};
 
-fn get_js_content(mut args: Args) -> io::Result<String> {
+fn get_js_path(mut args: Args) -> io::Result<String> {
     let path: String = args.nth(1).expect("<path> is not provided");
-    let path = Path::new(&path);
-    if path.extension().and_then(|ext| ext.to_str()) != Some("js") {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "only .js files are accepted",
-        ));
+    if Path::new(&path).extension().and_then(|ext| ext.to_str()) != Some("js") {
+        return Err(io::Error::other("only .js files are accepted"));
     }
 
-    Ok(fs::read_to_string(path)?)
+    Ok(path)
+}
+
+fn report(diagnostics: &[Diagnostic], path: &str, source: &str) {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic.render(path, source));
+    }
 }
 
 fn main() -> io::Result<()> {
-    let source = get_js_content(env::args())?;
+    // deep recursion in the script needs more stack than the main thread has
+    let run = thread::Builder::new().stack_size(STACK_SIZE).spawn(run)?;
+    run.join().unwrap_or_else(|_| process::exit(101))
+}
 
-    let mut tokenizer = Tokenizer::new(source);
-    let tokens = tokenizer.tokenize();
+fn run() -> io::Result<()> {
+    let path = get_js_path(env::args())?;
+    let source = fs::read_to_string(&path)?;
+
+    let mut tokenizer = Tokenizer::new(source.clone());
+    let tokens = match tokenizer.tokenize() {
+        Ok(tokens) => tokens,
+        Err(errors) => {
+            let errors: Vec<Diagnostic> = errors.into_iter().map(Diagnostic::from).collect();
+            report(&errors, &path, &source);
+            process::exit(1);
+        }
+    };
 
     for token in tokens.iter() {
         println!("{:?}", token);
     }
 
     let mut parser = Parser::new(tokens);
-    match parser.parse() {
-        Ok(ast) => {
-            println!("AST: {:#?}", ast);
-            let mut runtime = Runtime::new();
-            let _ = runtime.interpret(ast);
-        }
-        Err(e) => eprintln!("Parse error: {}", e),
-    };
+    let (ast, errors) = parser.parse();
+    if !errors.is_empty() {
+        report(&errors, &path, &source);
+        process::exit(1);
+    }
+
+    println!("AST: {:#?}", ast);
+    let mut runtime = Runtime::new();
+    if let Err(error) = runtime.interpret(ast) {
+        report(&[error], &path, &source);
+        process::exit(1);
+    }
 
     Ok(())
 }
@@ -1,10 +1,26 @@
+use std::collections::HashMap;
 use crate::common::ast::*;
-use crate::common::{Token, TokenType, Literal};
+use crate::common::{Token, TokenType, Literal, Diagnostic, Span};
+
+/// The global constants `Runtime::new` declares, which scripts can't declare again.
+const GLOBAL_CONSTANTS: [&str; 3] = ["NaN", "Infinity", "undefined"];
 
 #[derive(Debug)]
 pub struct Parser {
     tokens: Vec<Token>,
     position: usize,
+    diagnostics: Vec<Diagnostic>,
+    // the `let` and `const` names of every scope being parsed, innermost last,
+    // with where they are declared, or `None` for the global constants
+    scopes: Vec<HashMap<String, Option<Span>>>,
+    // whether a function body is being parsed, where `return` is allowed
+    in_function: bool,
+    // labels of the statements being parsed, with whether they label a loop
+    labels: Vec<(String, bool)>,
+    // number of loops being parsed, `break` and `continue` need one
+    loop_depth: usize,
+    // number of switches being parsed, an unlabeled `break` can also leave them
+    switch_depth: usize,
 }
 
 impl Parser {
@@ -12,92 +28,515 @@ impl Parser {
         Self {
             tokens,
             position: 0,
+            diagnostics: vec![],
+            scopes: vec![GLOBAL_CONSTANTS.iter().map(|name| (name.to_string(), None)).collect()],
+            in_function: false,
+            labels: vec![],
+            loop_depth: 0,
+            switch_depth: 0,
         }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Statement>, String> {
-        self.program()
+    /// Parses the whole program, recovering from syntax errors.
+    ///
+    /// Statements that failed to parse are left out of the returned AST and
+    /// every error found along the way is returned next to it.
+    pub fn parse(&mut self) -> (Vec<Statement>, Vec<Diagnostic>) {
+        let statements = self.program();
+        (statements, std::mem::take(&mut self.diagnostics))
     }
 
-    fn program(&mut self) -> Result<Vec<Statement>, String> {
+    fn program(&mut self) -> Vec<Statement> {
         let mut statements = vec![];
 
         while !self.is_end() && self.peek().kind != TokenType::Eof {
-            statements.push(self.statement()?);
+            let start = self.position;
+            match self.statement() {
+                Ok(statement) => statements.push(statement),
+                Err(error) => self.recover(error, start),
+            }
         }
 
-        if self.is_end() {
-            self.consume_token_type(TokenType::Eof, "expected end of file")?;
+        statements
+    }
+
+    /// Panic-mode recovery: records the error and skips tokens until the end of
+    /// the broken statement, which is a `;`, a `}` or the next statement keyword.
+    /// Braces opened in the skipped tokens are skipped up to their `}`.
+    fn recover(&mut self, error: Diagnostic, start: usize) {
+        self.diagnostics.push(error);
+
+        // always make progress, otherwise the same token fails forever
+        if self.position == start {
+            self.consume_token();
         }
 
-        Ok(statements)
+        let mut depth = 0;
+        while !self.is_end() && (depth > 0 || self.previous().kind != TokenType::Semicolon) {
+            match self.peek().kind {
+                TokenType::Eof => return,
+                TokenType::LeftCurlyBrace => {
+                    depth += 1;
+                    self.consume_token();
+                }
+                // the broken statement ends with the block it opened
+                TokenType::RightCurlyBrace if depth > 0 => {
+                    depth -= 1;
+                    self.consume_token();
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ if depth > 0 => {
+                    self.consume_token();
+                }
+                TokenType::RightCurlyBrace
+                | TokenType::KeywordLet
+                | TokenType::KeywordConst
+                | TokenType::Function
+                | TokenType::Return
+                | TokenType::If
+                | TokenType::While
+                | TokenType::Do
+                | TokenType::For
+                | TokenType::Break
+                | TokenType::Continue
+                | TokenType::Switch
+                | TokenType::Case
+                | TokenType::Default
+                | TokenType::Throw
+                | TokenType::Try => return,
+                _ => {
+                    self.consume_token();
+                }
+            }
+        }
     }
 
-    fn statement(&mut self) -> Result<Statement, String> {
+    fn statement(&mut self) -> Result<Statement, Diagnostic> {
         let token = self.peek();
 
         match token.kind {
             TokenType::KeywordLet | TokenType::KeywordConst => self.declaration(),
+            TokenType::Return if !self.in_function => {
+                Err(Diagnostic::error("E0110", "return statements can't be used outside of functions", token.span))
+            }
             TokenType::Function => self.function_declaration(),
             TokenType::Return => self.return_statement(),
+            TokenType::If => self.if_statement(),
+            TokenType::LeftCurlyBrace => self.block(),
+            TokenType::While => self.while_statement(),
+            TokenType::Do => self.do_while_statement(),
+            TokenType::For => self.for_statement(),
+            TokenType::Switch => self.switch_statement(),
+            TokenType::Throw => self.throw_statement(),
+            TokenType::Try => self.try_statement(),
+            TokenType::Break | TokenType::Continue => self.jump_statement(),
+            TokenType::Identifier(_) if self.peek_next().kind == TokenType::Colon => self.labeled_statement(),
             _ => self.expression_statement(),
         }
     }
 
-    fn declaration(&mut self) -> Result<Statement, String> {
-        let token = self.consume_token();
-        let is_const = match token.kind {
-            TokenType::KeywordLet => false,
-            TokenType::KeywordConst => true,
+    fn while_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
+        self.consume_token_type(TokenType::LeftParen, "expected '(' after while")?;
+        let test = self.sequence()?;
+        self.consume_token_type(TokenType::RightParen, "expected ')' after while condition")?;
+        let body = self.loop_body()?;
+
+        Ok(Statement::While { span: start.merge(body.span()), test: Box::new(test), body: Box::new(body) })
+    }
+
+    fn do_while_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
+        let body = self.loop_body()?;
+        self.consume_token_type(TokenType::While, "expected 'while' after do statement body")?;
+        self.consume_token_type(TokenType::LeftParen, "expected '(' after while")?;
+        let test = self.sequence()?;
+        let mut end = self.consume_token_type(TokenType::RightParen, "expected ')' after while condition")?.span;
+
+        // ASI always ends a do-while after its `)`, even on the same line
+        if self.peek().kind == TokenType::Semicolon {
+            end = self.consume_token().span;
+        }
+
+        Ok(Statement::DoWhile { body: Box::new(body), test: Box::new(test), span: start.merge(end) })
+    }
+
+    /// A `for` of any kind, its head is a scope around the body.
+    fn for_statement(&mut self) -> Result<Statement, Diagnostic> {
+        self.scopes.push(HashMap::new());
+        let statement = self.for_statement_in_scope();
+        self.scopes.pop();
+        statement
+    }
+
+    fn for_statement_in_scope(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
+        self.consume_token_type(TokenType::LeftParen, "expected '(' after for")?;
+
+        // no semicolons are inserted in the head of a for
+        self.reject_destructuring_pattern()?;
+        let init = match self.peek().kind {
+            TokenType::Semicolon => None,
+            TokenType::KeywordLet | TokenType::KeywordConst => {
+                let (is_const, name, value, declaration_start) = self.declaration_binding()?;
+                let span = declaration_start.merge(self.previous().span);
+                if self.at_for_in_or_of() && value.is_some() {
+                    return Err(Diagnostic::error("E0103", format!("'{}' can't have an initializer here", name), span)
+                        .with_note("the variable of a for...in or for...of gets its values from the loop"));
+                }
+                if !self.at_for_in_or_of() && is_const && value.is_none() {
+                    return Err(self.missing_const_initializer(declaration_start));
+                }
+                Some(Box::new(Statement::Declaration { is_const, name, value: Box::new(value), span }))
+            }
             _ => {
-                return Err(format!(
-                    "line {}: declaration expects let or const instead of {:?}",
-                    token.line, token.kind
-                ))
+                let expression = self.sequence()?;
+                if self.at_for_in_or_of() && !matches!(expression, Expression::Identifier { .. }) {
+                    return Err(Diagnostic::error("E0105", "invalid assignment target", expression.span())
+                        .with_note("only variables can be assigned to"));
+                }
+                let span = expression.span();
+                Some(Box::new(Statement::ExpressionStatement { expression: Box::new(expression), span }))
             }
         };
 
-        let name = self.identifier()?;
-        let name = Expression::extract_string(&name)
-            .ok_or_else(|| format!("Expected declaration name to be Expression::Identifier but got {:?}", name))?;
+        if self.at_for_in_or_of() {
+            if let Some(left) = init {
+                return self.for_in_or_of_statement(start, left);
+            }
+        }
+        self.consume_token_type(TokenType::Semicolon, "expected ';' after for initializer")?;
 
-        let mut value = None;
+        let mut test = None;
+        if self.peek().kind != TokenType::Semicolon {
+            test = Some(Box::new(self.sequence()?));
+        }
+        self.consume_token_type(TokenType::Semicolon, "expected ';' after for condition")?;
+
+        let mut update = None;
+        if self.peek().kind != TokenType::RightParen {
+            update = Some(Box::new(self.sequence()?));
+        }
+        self.consume_token_type(TokenType::RightParen, "expected ')' after for clauses")?;
+
+        let body = self.loop_body()?;
+        Ok(Statement::For { init, test, update, span: start.merge(body.span()), body: Box::new(body) })
+    }
+
+    /// Rejects an array or object pattern at the start of a for head, after a
+    /// `let` or `const` if there is one. Arrays and objects don't exist yet.
+    fn reject_destructuring_pattern(&self) -> Result<(), Diagnostic> {
+        let mut token = self.peek();
+        if matches!(token.kind, TokenType::KeywordLet | TokenType::KeywordConst) {
+            token = self.peek_next();
+        }
+        if matches!(token.kind, TokenType::LeftSquareBracket | TokenType::LeftCurlyBrace) {
+            return Err(Diagnostic::error("E0112", "destructuring patterns are not supported", token.span)
+                .with_note("declare a single variable in the head of the loop"));
+        }
+        Ok(())
+    }
+
+    fn for_in_or_of_statement(&mut self, start: Span, left: Box<Statement>) -> Result<Statement, Diagnostic> {
+        let is_of = self.consume_token().kind != TokenType::In;
+        // a comma expression is allowed after `in` only
+        let right = Box::new(if is_of { self.assignment()? } else { self.sequence()? });
+        self.consume_token_type(TokenType::RightParen, "expected ')' after for clauses")?;
+
+        let body = self.loop_body()?;
+        let (span, body) = (start.merge(body.span()), Box::new(body));
+        Ok(if is_of {
+            Statement::ForOf { left, right, body, span }
+        } else {
+            Statement::ForIn { left, right, body, span }
+        })
+    }
 
+    /// Whether the head of a for continues with `in` or the contextual keyword `of`.
+    fn at_for_in_or_of(&self) -> bool {
+        match &self.peek().kind {
+            TokenType::In => true,
+            TokenType::Identifier(name) => name == "of",
+            _ => false,
+        }
+    }
+
+    fn loop_body(&mut self) -> Result<Statement, Diagnostic> {
+        self.loop_depth += 1;
+        let body = self.substatement();
+        self.loop_depth -= 1;
+        body
+    }
+
+    fn switch_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
+        self.consume_token_type(TokenType::LeftParen, "expected '(' after switch")?;
+        let discriminant = self.sequence()?;
+        self.consume_token_type(TokenType::RightParen, "expected ')' after switch discriminant")?;
+        self.consume_token_type(TokenType::LeftCurlyBrace, "expected '{' to begin switch cases")?;
+
+        // all the cases share one scope
+        self.switch_depth += 1;
+        self.scopes.push(HashMap::new());
+        let cases = self.switch_cases();
+        self.scopes.pop();
+        self.switch_depth -= 1;
+        let cases = cases?;
+
+        let end = self.consume_token_type(TokenType::RightCurlyBrace, "expected '}' to end switch")?.span;
+        Ok(Statement::Switch { discriminant: Box::new(discriminant), cases, span: start.merge(end) })
+    }
+
+    fn switch_cases(&mut self) -> Result<Vec<SwitchCase>, Diagnostic> {
+        let mut cases = vec![];
+        let mut default_span: Option<Span> = None;
+
+        while !matches!(self.peek().kind, TokenType::RightCurlyBrace | TokenType::Eof) {
+            let token = self.consume_token();
+            let start = token.span;
+            let test = match token.kind {
+                TokenType::Case => Some(self.sequence()?),
+                TokenType::Default => {
+                    if let Some(previous) = default_span {
+                        return Err(Diagnostic::error("E0107", "a switch can only have one default clause", start)
+                            .with_label(previous, "first default clause is here"));
+                    }
+                    default_span = Some(start);
+                    None
+                }
+                _ => {
+                    return Err(Diagnostic::error("E0101", "expected 'case' or 'default'", start)
+                        .with_note(format!("found {:?}", token.kind)))
+                }
+            };
+            let colon = self.consume_token_type(TokenType::Colon, "expected ':' after case")?.span;
+
+            let mut consequent = vec![];
+            while !matches!(
+                self.peek().kind,
+                TokenType::Case | TokenType::Default | TokenType::RightCurlyBrace | TokenType::Eof
+            ) {
+                let start = self.position;
+                match self.statement() {
+                    Ok(statement) => consequent.push(statement),
+                    Err(error) => self.recover(error, start),
+                }
+            }
+
+            let end = consequent.last().map_or(colon, Statement::span);
+            cases.push(SwitchCase { test, consequent, span: start.merge(end) });
+        }
+
+        Ok(cases)
+    }
+
+    /// `break` or `continue`, with an optional label on the same line.
+    fn jump_statement(&mut self) -> Result<Statement, Diagnostic> {
         let token = self.consume_token();
-        match token.kind {
-            TokenType::Assign => {
-                value = Some(self.comparison()?);
+        let (is_break, start) = (token.kind == TokenType::Break, token.span);
+
+        let mut label = None;
+        if let TokenType::Identifier(name) = &self.peek().kind {
+            if !self.peek().newline_before {
+                label = Some(name.clone());
+                self.consume_token();
+            }
+        }
+
+        let keyword = if is_break { "break" } else { "continue" };
+        match &label {
+            Some(name) => match self.labels.iter().find(|(label, _)| label == name) {
+                None => {
+                    return Err(Diagnostic::error("E0106", format!("undefined label '{}'", name), self.previous().span))
+                }
+                Some((_, false)) if !is_break => {
+                    return Err(Diagnostic::error("E0106", format!("label '{}' does not label a loop", name), self.previous().span)
+                        .with_note("continue can only jump to a loop"))
+                }
+                _ => (),
+            },
+            None if is_break && self.loop_depth == 0 && self.switch_depth == 0 => {
+                return Err(Diagnostic::error("E0106", "break must be inside of a loop or switch", start))
+            }
+            None if !is_break && self.loop_depth == 0 => {
+                return Err(Diagnostic::error("E0106", "continue must be inside of a loop", start))
+            }
+            None => (),
+        }
+
+        let end = self
+            .end_of_statement()
+            .ok_or_else(|| self.expected_semicolon(&format!("expected ';' after {}", keyword)))?;
+        let span = start.merge(end);
+        Ok(if is_break { Statement::Break { label, span } } else { Statement::Continue { label, span } })
+    }
+
+    fn labeled_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let token = self.consume_token();
+        let (label, start) = match &token.kind {
+            TokenType::Identifier(name) => (name.clone(), token.span),
+            _ => return Err(Diagnostic::error("E0101", format!("label expected instead of {:?}", token.kind), token.span)),
+        };
+        self.consume_token_type(TokenType::Colon, "expected ':' after label")?;
+
+        if self.labels.iter().any(|(existing, _)| *existing == label) {
+            return Err(Diagnostic::error("E0106", format!("label '{}' is already declared", label), start));
+        }
+
+        // skip any more labels to find out whether a loop is labeled
+        let mut position = self.position;
+        while matches!(self.tokens.get(position).map(|t| &t.kind), Some(TokenType::Identifier(_)))
+            && self.tokens.get(position + 1).map(|t| &t.kind) == Some(&TokenType::Colon)
+        {
+            position += 2;
+        }
+        let is_loop = matches!(
+            self.tokens.get(position).map(|t| &t.kind),
+            Some(TokenType::While | TokenType::Do | TokenType::For)
+        );
+
+        self.labels.push((label.clone(), is_loop));
+        let body = self.substatement();
+        self.labels.pop();
+        let body = body?;
+
+        Ok(Statement::Labeled { label, span: start.merge(body.span()), body: Box::new(body) })
+    }
+
+    fn if_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
+        self.consume_token_type(TokenType::LeftParen, "expected '(' after if")?;
+        let test = self.sequence()?;
+        self.consume_token_type(TokenType::RightParen, "expected ')' after if condition")?;
+
+        let consequent = self.substatement()?;
+        let mut end = consequent.span();
+        let mut alternate = None;
+        if self.peek().kind == TokenType::Else {
+            self.consume_token();
+            let statement = self.substatement()?;
+            end = statement.span();
+            alternate = Some(Box::new(statement));
+        }
+
+        Ok(Statement::If {
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate,
+            span: start.merge(end),
+        })
+    }
+
+    /// A statement nested in another one, like the branches of an `if`, where
+    /// declarations are not allowed without a block around them.
+    fn substatement(&mut self) -> Result<Statement, Diagnostic> {
+        let token = self.peek();
+        if matches!(token.kind, TokenType::KeywordLet | TokenType::KeywordConst | TokenType::Function) {
+            return Err(Diagnostic::error("E0103", "declarations can't be used as a single statement", token.span)
+                .with_note("wrap the declaration in a block: { ... }"));
+        }
+
+        self.statement()
+    }
+
+    fn block(&mut self) -> Result<Statement, Diagnostic> {
+        self.block_in_scope(HashMap::new())
+    }
 
-                self.consume_token_type(TokenType::Semicolon, "expected ';' after declaration")?;
+    /// A block whose scope starts with the names of `scope` declared, like a catch parameter.
+    fn block_in_scope(&mut self, scope: HashMap<String, Option<Span>>) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token_type(TokenType::LeftCurlyBrace, "expected '{' to begin block")?.span;
+
+        let mut body = vec![];
+        self.scopes.push(scope);
+        while !matches!(self.peek().kind, TokenType::RightCurlyBrace | TokenType::Eof) {
+            let start = self.position;
+            match self.statement() {
+                Ok(statement) => body.push(statement),
+                Err(error) => self.recover(error, start),
             }
-            TokenType::Semicolon => (),
+        }
+        self.scopes.pop();
+
+        let end = self.consume_token_type(TokenType::RightCurlyBrace, "expected '}' to end block")?.span;
+        Ok(Statement::Block { body, span: start.merge(end) })
+    }
+
+    fn declaration(&mut self) -> Result<Statement, Diagnostic> {
+        let (is_const, name, value, start) = self.declaration_binding()?;
+        if is_const && value.is_none() {
+            return Err(self.missing_const_initializer(start));
+        }
+
+        let end = match self.end_of_statement() {
+            Some(end) => end,
+            None if value.is_some() => return Err(self.expected_semicolon("expected ';' after declaration")),
+            None => {
+                let token = self.peek();
+                return Err(Diagnostic::error(
+                    "E0103",
+                    format!("declaration expects '=' or ';' instead of {:?}", token.kind),
+                    token.span,
+                ))
+            }
+        };
+
+        Ok(Statement::Declaration { is_const, name, value: Box::new(value), span: start.merge(end) })
+    }
+
+    /// The part of a declaration before its `;`: the kind, the name and the initializer.
+    fn declaration_binding(&mut self) -> Result<(bool, String, Option<Expression>, Span), Diagnostic> {
+        let token = self.consume_token();
+        let start = token.span;
+        let is_const = match token.kind {
+            TokenType::KeywordLet => false,
+            TokenType::KeywordConst => true,
             _ => {
-                return Err(format!(
-                    "line {}: declaration expects '=' or ';' instead of {:?}",
-                    token.line, token.kind
+                return Err(Diagnostic::error(
+                    "E0103",
+                    format!("declaration expects let or const instead of {:?}", token.kind),
+                    token.span,
                 ))
             }
         };
 
-        Ok(Statement::Declaration { is_const, name, value: Box::new(value) })
+        let identifier = self.identifier()?;
+        let name = Expression::extract_string(&identifier)
+            .ok_or_else(|| Diagnostic::error("E0103", format!("Expected declaration name to be Expression::Identifier but got {:?}", identifier), identifier.span()))?;
+        self.declare(&name, identifier.span())?;
+
+        let mut value = None;
+        if self.peek().kind == TokenType::Assign {
+            self.consume_token();
+            value = Some(self.assignment()?);
+        }
+
+        Ok((is_const, name, value, start))
+    }
+
+    fn missing_const_initializer(&self, start: Span) -> Diagnostic {
+        Diagnostic::error("E0103", "Missing initializer in const declaration", start.merge(self.previous().span))
+            .with_note("a constant gets its value where it is declared")
     }
 
-    fn expression_statement(&mut self) -> Result<Statement, String> {
-        let expr = self.comparison()?;
-        self.consume_token_type(
-            TokenType::Semicolon,
-            "expected ';' after expression statement",
-        )?;
+    fn expression_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let expr = self.sequence()?;
+        let end = self
+            .end_of_statement()
+            .ok_or_else(|| self.expected_semicolon("expected ';' after expression statement"))?;
         Ok(Statement::ExpressionStatement {
+            span: expr.span().merge(end),
             expression: Box::new(expr),
         })
     }
 
-    fn function_declaration(&mut self) -> Result<Statement, String> {
-        let _ = self.consume_token();
+    fn function_declaration(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
         let identifier = self.identifier()?;
         let name = Expression::extract_string(&identifier)
-            .ok_or_else(|| format!("Expected declaration name to be Expression::Identifier but got {:?}", identifier))?;
+            .ok_or_else(|| Diagnostic::error("E0103", format!("Expected declaration name to be Expression::Identifier but got {:?}", identifier), identifier.span()))?;
         
         self.consume_token_type(TokenType::LeftParen, "expected '(' after function name")?;
         let mut params = vec![];
@@ -106,34 +545,120 @@ impl Parser {
         }
 
         self.consume_token_type(TokenType::RightParen, "expected ')' after function arguments")?;
-        let mut body = self.function_body()?;
-        if body.len() == 0 {
-            body = vec![ Statement::Return { expression: Box::new(Expression::Literal(Literal::Undefined))}];
+        let mut body = self.function_body(&params)?;
+        let end = self.previous().span;
+        if body.is_empty() {
+            body = vec![Statement::Return {
+                expression: Box::new(Expression::Literal { value: Literal::Undefined, span: end }),
+                span: end,
+            }];
         }
         
-        Ok(Statement::FunctionDeclaration { name, params, body: Box::new(body) })
+        Ok(Statement::FunctionDeclaration { name, params, body, span: start.merge(end) })
     }
 
-    fn return_statement(&mut self) -> Result<Statement, String> {
-        let _ = self.consume_token();
+    fn return_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
 
-        if self.peek().kind == TokenType::Semicolon {
-            let _ = self.consume_token();
-            return Ok(Statement::Return { expression: Box::new(Expression::Literal(Literal::Undefined)) });
+        // restricted production: a line break after `return` ends the statement
+        if self.peek().newline_before || matches!(self.peek().kind, TokenType::Semicolon | TokenType::RightCurlyBrace | TokenType::Eof) {
+            let end = self.end_of_statement().unwrap_or(start);
+            return Ok(Statement::Return {
+                expression: Box::new(Expression::Literal { value: Literal::Undefined, span: start }),
+                span: start.merge(end),
+            });
         }
         
-        let expr = self.comparison()?;
-        self.consume_token_type(TokenType::Semicolon, "expected ';' after return statement")?;
+        let expr = self.sequence()?;
+        let end = self
+            .end_of_statement()
+            .ok_or_else(|| self.expected_semicolon("expected ';' after return statement"))?;
         
-        Ok(Statement::Return { expression: Box::new(expr) })
+        Ok(Statement::Return { expression: Box::new(expr), span: start.merge(end) })
     }
 
-    fn function_params(&mut self) -> Result<Vec<String>, String> {
+    fn throw_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
+
+        // restricted production, but unlike `return` there is nothing to throw without an expression
+        if self.peek().newline_before {
+            return Err(Diagnostic::error("E0102", "expected an expression on the same line as throw", self.peek().span)
+                .with_note("a line break after throw would end the statement"));
+        }
+
+        let expr = self.sequence()?;
+        let end = self
+            .end_of_statement()
+            .ok_or_else(|| self.expected_semicolon("expected ';' after throw statement"))?;
+
+        Ok(Statement::Throw { expression: Box::new(expr), span: start.merge(end) })
+    }
+
+    fn try_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.consume_token().span;
+        let block = self.block()?;
+
+        let mut handler = None;
+        if self.peek().kind == TokenType::Catch {
+            let catch_start = self.consume_token().span;
+            let mut param = None;
+            let mut scope = HashMap::new();
+            if self.peek().kind == TokenType::LeftParen {
+                self.consume_token();
+                let name = self.identifier()?;
+                param = Expression::extract_string(&name);
+                if let Some(param) = &param {
+                    // the parameter shares a scope with the catch block
+                    scope.insert(param.clone(), Some(name.span()));
+                }
+                self.consume_token_type(TokenType::RightParen, "expected ')' after catch parameter")?;
+            }
+
+            let body = self.block_in_scope(scope)?;
+            handler = Some(CatchClause { param, span: catch_start.merge(body.span()), body: Box::new(body) });
+        }
+
+        let mut finalizer = None;
+        if self.peek().kind == TokenType::Finally {
+            self.consume_token();
+            finalizer = Some(Box::new(self.block()?));
+        }
+
+        let end = match (&handler, &finalizer) {
+            (_, Some(finalizer)) => finalizer.span(),
+            (Some(handler), None) => handler.span,
+            (None, None) => {
+                let token = self.peek();
+                return Err(Diagnostic::error("E0101", "expected 'catch' or 'finally' after try block", token.span)
+                    .with_note(format!("found {:?}", token.kind)));
+            }
+        };
+
+        Ok(Statement::Try { block: Box::new(block), handler, finalizer, span: start.merge(end) })
+    }
+
+    /// Records a `let` or `const` name in the innermost scope, which can't have it twice.
+    fn declare(&mut self, name: &str, span: Span) -> Result<(), Diagnostic> {
+        let Some(scope) = self.scopes.last_mut() else {
+            return Ok(());
+        };
+        if let Some(previous) = scope.get(name) {
+            let error = Diagnostic::error("E0109", format!("variable {} already declared", name), span);
+            return Err(match previous {
+                Some(previous) => error.with_label(*previous, "first declared here"),
+                None => error.with_note(format!("{} is a global constant", name)),
+            });
+        }
+        scope.insert(name.to_string(), Some(span));
+        Ok(())
+    }
+
+    fn function_params(&mut self) -> Result<Vec<String>, Diagnostic> {
         let mut params = vec![];
 
         let param = self.identifier()?;
         let param: String = Expression::extract_string(&param)
-            .ok_or_else(|| format!("Expected function parameter to be Expression::Identifier but got {:?}", param))?;
+            .ok_or_else(|| Diagnostic::error("E0101", format!("Expected function parameter to be Expression::Identifier but got {:?}", param), param.span()))?;
 
         params.push(param);
         
@@ -141,7 +666,7 @@ impl Parser {
             self.consume_token();
             let param = self.identifier()?;
             let param: String = Expression::extract_string(&param)
-                .ok_or_else(|| format!("Expected function parameter to be Expression::Identifier but got {:?}", param))?;
+                .ok_or_else(|| Diagnostic::error("E0101", format!("Expected function parameter to be Expression::Identifier but got {:?}", param), param.span()))?;
 
             params.push(param);
         }
@@ -149,12 +674,26 @@ impl Parser {
         Ok(params)
     }
 
-    fn function_body(&mut self) -> Result<Vec<Statement>, String> {
-        self.consume_token_type(TokenType::LeftCurlyBrace, "Expected '{' to begin function body.")?;
+    fn function_body(&mut self, params: &[String]) -> Result<Vec<Statement>, Diagnostic> {
+        let start = self.consume_token_type(TokenType::LeftCurlyBrace, "Expected '{' to begin function body.")?.span;
 
         let mut statements = vec![];
         if self.peek().kind != TokenType::RightCurlyBrace {
-            statements = self.function_body_content()?;
+            // labels and loops around a function don't reach into its body,
+            // and the parameters share a scope with it
+            let labels = std::mem::take(&mut self.labels);
+            let loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+            let switch_depth = std::mem::replace(&mut self.switch_depth, 0);
+            let in_function = std::mem::replace(&mut self.in_function, true);
+            let scope = params.iter().map(|param| (param.clone(), Some(start))).collect();
+            let scopes = std::mem::replace(&mut self.scopes, vec![scope]);
+            let content = self.function_body_content();
+            self.labels = labels;
+            self.loop_depth = loop_depth;
+            self.switch_depth = switch_depth;
+            self.in_function = in_function;
+            self.scopes = scopes;
+            statements = content?;
         }
         
         self.consume_token_type(TokenType::RightCurlyBrace, "Expected '}' to end function body.")?;
@@ -162,42 +701,155 @@ impl Parser {
         Ok(statements)
     }
 
-    fn function_body_content(&mut self) -> Result<Vec<Statement>, String> {
+    fn function_body_content(&mut self) -> Result<Vec<Statement>, Diagnostic> {
         let mut statements = vec![];
         let mut is_return_found = false;
-        while self.peek().kind != TokenType::RightCurlyBrace {
-            let statement = match self.peek().kind {
-                TokenType::Function => return Err(format!("line {}: Functions inside functions are not yet supported", self.peek().line)),
-                TokenType::Return => {
-                    is_return_found = true;
-                    self.return_statement()
-                },
-                TokenType::KeywordLet | TokenType::KeywordConst => self.declaration(),
-                _ => self.expression_statement(),
-            };
-            statements.push(statement?);
+        while !matches!(self.peek().kind, TokenType::RightCurlyBrace | TokenType::Eof) {
+            let start = self.position;
+            let statement = self.statement();
+            is_return_found |= matches!(statement, Ok(Statement::Return { .. }));
+            match statement {
+                Ok(statement) => statements.push(statement),
+                Err(error) => self.recover(error, start),
+            }
         }
 
         if !is_return_found {
-            statements.push(Statement::Return { expression: Box::new(Expression::Literal(Literal::Undefined)) });
+            let span = self.peek().span;
+            statements.push(Statement::Return {
+                expression: Box::new(Expression::Literal { value: Literal::Undefined, span }),
+                span,
+            });
         }
 
         Ok(statements)
     }
     
-    fn comparison(&mut self) -> Result<Expression, String> {
-        let mut expr = self.expression()?;
+    /// The comma operator, `a, b` evaluates both and results in `b`.
+    fn sequence(&mut self) -> Result<Expression, Diagnostic> {
+        let expr = self.assignment()?;
+        if self.peek().kind != TokenType::Comma {
+            return Ok(expr);
+        }
+
+        let mut expressions = vec![expr];
+        while self.peek().kind == TokenType::Comma {
+            self.consume_token();
+            expressions.push(self.assignment()?);
+        }
+
+        let span = expressions[0].span().merge(expressions[expressions.len() - 1].span());
+        Ok(Expression::Sequence { expressions, span })
+    }
+
+    fn assignment(&mut self) -> Result<Expression, Diagnostic> {
+        let target = self.conditional()?;
+        if !self.peek().kind.is_assignment_operator() {
+            return Ok(target);
+        }
+
+        let operator = self.consume_token().kind.clone();
+        // right-associative, `a = b = 1` assigns `b = 1` first
+        let value = self.assignment()?;
+
+        Ok(Expression::Assignment {
+            span: target.span().merge(value.span()),
+            target: Box::new(self.simple_assignment_target(&target)?),
+            operator,
+            value: Box::new(value),
+        })
+    }
+
+    /// `test ? consequent : alternate`, right-associative like assignments.
+    fn conditional(&mut self) -> Result<Expression, Diagnostic> {
+        let test = self.logical_or()?;
+        if self.peek().kind != TokenType::Question {
+            return Ok(test);
+        }
+
+        self.consume_token();
+        let consequent = self.assignment()?;
+        self.consume_token_type(TokenType::Colon, "expected ':' in conditional expression")?;
+        let alternate = self.assignment()?;
+
+        Ok(Expression::Conditional {
+            span: test.span().merge(alternate.span()),
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate: Box::new(alternate),
+        })
+    }
+
+    /// `||` and `??`, which share a precedence level but can't be mixed with
+    /// each other or with `&&` unless one side is in parentheses.
+    fn logical_or(&mut self) -> Result<Expression, Diagnostic> {
+        let mut expr = self.logical_and()?;
+
+        while matches!(self.peek().kind, TokenType::PipePipe | TokenType::QuestionQuestion) {
+            let operator = self.consume_token().kind.clone();
+
+            let right_operand = self.logical_and()?;
+            for operand in [&expr, &right_operand] {
+                if let Expression::Logical { operator: inner, span, .. } = operand {
+                    if (operator == TokenType::QuestionQuestion) != (*inner == TokenType::QuestionQuestion) {
+                        return Err(Diagnostic::error("E0108", "'??' can't be mixed with '&&' or '||'", operand.span())
+                            .with_label(*span, "wrap this in parentheses"));
+                    }
+                }
+            }
+
+            expr = Expression::Logical {
+                span: expr.span().merge(right_operand.span()),
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right_operand),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn logical_and(&mut self) -> Result<Expression, Diagnostic> {
+        let mut expr = self.bitwise_or()?;
+
+        while self.peek().kind == TokenType::AmpersandAmpersand {
+            let operator = self.consume_token().kind.clone();
+
+            let right_operand = self.bitwise_or()?;
+            expr = Expression::Logical {
+                span: expr.span().merge(right_operand.span()),
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right_operand),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn bitwise_or(&mut self) -> Result<Expression, Diagnostic> {
+        self.binary_level(&[TokenType::Pipe], Self::bitwise_xor)
+    }
+
+    fn bitwise_xor(&mut self) -> Result<Expression, Diagnostic> {
+        self.binary_level(&[TokenType::Caret], Self::bitwise_and)
+    }
+
+    fn bitwise_and(&mut self) -> Result<Expression, Diagnostic> {
+        self.binary_level(&[TokenType::Ampersand], Self::equality)
+    }
 
-        while self.peek().kind.is_comparison_operator() {
-            let operator_token = self.consume_token();
-            // workaround: cannot borrow `*self` as mutable more than once
-            let operator_token: Token =
-                Token::new(operator_token.kind.clone(), operator_token.line);
+    fn equality(&mut self) -> Result<Expression, Diagnostic> {
+        let mut expr = self.relational()?;
 
-            let right_operand = self.expression()?;
+        while self.peek().kind.is_equality_operator() {
+            let operator = self.consume_token().kind.clone();
+
+            let right_operand = self.relational()?;
             expr = Expression::Comparison {
+                span: expr.span().merge(right_operand.span()),
                 left: Box::new(expr),
-                operator: operator_token.kind,
+                operator,
                 right: Box::new(right_operand),
             };
         }
@@ -205,19 +857,17 @@ impl Parser {
         Ok(expr)
     }
 
-    fn expression(&mut self) -> Result<Expression, String> {
-        let mut expr = self.term()?;
+    fn relational(&mut self) -> Result<Expression, Diagnostic> {
+        let mut expr = self.shift()?;
 
-        while matches!(self.peek().kind, TokenType::Plus | TokenType::Minus) {
-            let operator_token = self.consume_token();
-            // workaround: cannot borrow `*self` as mutable more than once
-            let operator_token: Token =
-                Token::new(operator_token.kind.clone(), operator_token.line);
+        while self.peek().kind.is_relational_operator() {
+            let operator = self.consume_token().kind.clone();
 
-            let right_operand = self.term()?;
-            expr = Expression::Binary {
+            let right_operand = self.shift()?;
+            expr = Expression::Comparison {
+                span: expr.span().merge(right_operand.span()),
                 left: Box::new(expr),
-                operator: operator_token.kind,
+                operator,
                 right: Box::new(right_operand),
             };
         }
@@ -225,18 +875,58 @@ impl Parser {
         Ok(expr)
     }
 
-    fn term(&mut self) -> Result<Expression, String> {
-        let mut expr = self.factor()?;
+    fn shift(&mut self) -> Result<Expression, Diagnostic> {
+        let operators = [TokenType::LeftShift, TokenType::RightShift, TokenType::UnsignedRightShift];
+        self.binary_level(&operators, Self::expression)
+    }
+
+    fn expression(&mut self) -> Result<Expression, Diagnostic> {
+        self.binary_level(&[TokenType::Plus, TokenType::Minus], Self::term)
+    }
 
-        while matches!(self.peek().kind, TokenType::Star | TokenType::Slash) {
-            let operator_token = self.consume_token();
-            // workaround: cannot borrow `*self` as mutable more than once
-            let operator_token = Token::new(operator_token.kind.clone(), operator_token.line);
+    fn term(&mut self) -> Result<Expression, Diagnostic> {
+        self.binary_level(&[TokenType::Star, TokenType::Slash, TokenType::Percent], Self::exponent)
+    }
+
+    /// `**` is right-associative, and a unary expression on its left needs
+    /// parentheses since `-2 ** 2` could mean either `(-2) ** 2` or `-(2 ** 2)`.
+    fn exponent(&mut self) -> Result<Expression, Diagnostic> {
+        let base = self.factor()?;
+        if self.peek().kind != TokenType::StarStar {
+            return Ok(base);
+        }
+
+        if let Expression::Unary { span, .. } = &base {
+            return Err(Diagnostic::error("E0111", "a unary expression can't be the base of '**'", *span)
+                .with_note("wrap either the unary expression or the exponentiation in parentheses"));
+        }
+
+        let operator = self.consume_token().kind.clone();
+        let exponent = self.exponent()?;
+        Ok(Expression::Binary {
+            span: base.span().merge(exponent.span()),
+            left: Box::new(base),
+            operator,
+            right: Box::new(exponent),
+        })
+    }
 
-            let right_operand = self.factor()?;
+    /// One level of left-associative binary operators, `operand` parses the next tighter level.
+    fn binary_level(
+        &mut self,
+        operators: &[TokenType],
+        operand: fn(&mut Self) -> Result<Expression, Diagnostic>,
+    ) -> Result<Expression, Diagnostic> {
+        let mut expr = operand(self)?;
+
+        while operators.contains(&self.peek().kind) {
+            let operator = self.consume_token().kind.clone();
+
+            let right_operand = operand(self)?;
             expr = Expression::Binary {
+                span: expr.span().merge(right_operand.span()),
                 left: Box::new(expr),
-                operator: operator_token.kind,
+                operator,
                 right: Box::new(right_operand),
             };
         }
@@ -244,108 +934,219 @@ impl Parser {
         Ok(expr)
     }
 
-    fn factor(&mut self) -> Result<Expression, String> {
+    fn factor(&mut self) -> Result<Expression, Diagnostic> {
         let token = self.peek();
 
-        match token.kind {
+        let mut expr = match token.kind {
             TokenType::Literal(_) => self.literal(),
+            TokenType::NoSubstitutionTemplate(_) | TokenType::TemplateHead(_) => self.template(),
             TokenType::LeftParen => self.grouping(),
-            TokenType::Minus => self.unary(),
-            TokenType::Identifier(_) => {
-                let mut expr = self.identifier()?;
-                if self.peek().kind == TokenType::LeftParen {
-                    self.go_to_previous_token();
-                    expr = self.call()?;
+            TokenType::Minus
+            | TokenType::Plus
+            | TokenType::Bang
+            | TokenType::Tilde
+            | TokenType::Typeof
+            | TokenType::Void
+            | TokenType::Delete
+            | TokenType::PlusPlus
+            | TokenType::MinusMinus => self.unary(),
+            TokenType::Identifier(_) => self.identifier(),
+            _ => Err(Diagnostic::error(
+                "E0102",
+                format!("Expected factor (number, '(', unary -) but got {:?}", token.kind),
+                token.span,
+            ))
+        }?;
+
+        loop {
+            match self.peek().kind {
+                TokenType::Dot => {
+                    self.consume_token();
+                    let property = self.identifier()?;
+                    let span = expr.span().merge(property.span());
+                    let property = Expression::extract_string(&property).unwrap_or_default();
+                    expr = Expression::Member { object: Box::new(expr), property, span };
                 }
+                TokenType::LeftParen => expr = self.call(expr)?,
+                _ => break,
+            }
+        }
 
-                Ok(expr)
-            },
-            _ => Err(format!("line {}: Expected factor (number, '(', unary -) but got {:?}", token.line, token.kind))
+        // restricted production: `a\n++b` is `a; ++b;`
+        if matches!(self.peek().kind, TokenType::PlusPlus | TokenType::MinusMinus) && !self.peek().newline_before {
+            let token = self.consume_token();
+            let (operator, end) = (token.kind.clone(), token.span);
+            let target = self.simple_assignment_target(&expr)?;
+            expr = Expression::Update { operator, prefix: false, span: expr.span().merge(end), target: Box::new(target) };
         }
-    }
 
-    fn call(&mut self) -> Result<Expression, String> {
-        let identifier: Expression = self.identifier()?;
-        let identifier = Expression::extract_string(&identifier)
-            .ok_or_else(|| format!("Expected function name as Expression::Identifier but got {:?}", identifier))?;
+        Ok(expr)
+    }
 
-        self.consume_token_type(TokenType::LeftParen, format!("expected '(' for {:?} function call", identifier).as_str())?;
+    fn call(&mut self, callee: Expression) -> Result<Expression, Diagnostic> {
+        let start = callee.span();
+        self.consume_token_type(TokenType::LeftParen, "expected '(' for function call")?;
         if self.peek().kind == TokenType::RightParen {
-            self.consume_token();
-            return Ok(Expression::Call { callee: identifier, args: Box::new(vec![]) });
+            let end = self.consume_token().span;
+            return Ok(Expression::Call { callee: Box::new(callee), args: vec![], span: start.merge(end) });
         }
 
         let args = self.arguments()?;
-        self.consume_token_type(TokenType::RightParen, format!("expected ')' for {:?} function call", identifier).as_str())?;
-        Ok(Expression::Call { callee: identifier, args: Box::new(args) })
-    } 
-    
-    fn arguments(&mut self) -> Result<Vec<Expression>, String> {
+        let end = self.consume_token_type(TokenType::RightParen, "expected ')' after function call arguments")?.span;
+        Ok(Expression::Call { callee: Box::new(callee), args, span: start.merge(end) })
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expression>, Diagnostic> {
         let mut args = vec![];
-        args.push(self.comparison()?);
+        args.push(self.assignment()?);
 
         while self.peek().kind == TokenType::Comma {
             self.consume_token();
-            args.push(self.comparison()?);
+            args.push(self.assignment()?);
         }
 
         Ok(args)
     }
 
-    fn literal(&mut self) -> Result<Expression, String> {
+    fn literal(&mut self) -> Result<Expression, Diagnostic> {
         let token = self.consume_token();
 
         match &token.kind {
-            TokenType::Literal(literal) => Ok(Expression::Literal(literal.clone())),
-            _ => Err(format!("line {}: Expected number literal but got {:?}", token.line, token.kind)),
+            TokenType::Literal(literal) => Ok(Expression::Literal { value: literal.clone(), span: token.span }),
+            _ => Err(Diagnostic::error("E0102", format!("Expected number literal but got {:?}", token.kind), token.span)),
+        }
+    }
+
+    fn template(&mut self) -> Result<Expression, Diagnostic> {
+        let token = self.consume_token();
+        let start = token.span;
+        let mut quasis = match &token.kind {
+            TokenType::NoSubstitutionTemplate(text) => {
+                return Ok(Expression::Template { quasis: vec![text.clone()], expressions: vec![], span: start });
+            }
+            TokenType::TemplateHead(text) => vec![text.clone()],
+            _ => return Err(Diagnostic::error("E0102", format!("Expected template literal but got {:?}", token.kind), token.span)),
+        };
+
+        let mut expressions = vec![];
+        loop {
+            expressions.push(self.sequence()?);
+
+            let token = self.consume_token();
+            match &token.kind {
+                TokenType::TemplateMiddle(text) => quasis.push(text.clone()),
+                TokenType::TemplateTail(text) => {
+                    quasis.push(text.clone());
+                    return Ok(Expression::Template { quasis, expressions, span: start.merge(token.span) });
+                }
+                _ => {
+                    return Err(Diagnostic::error("E0101", "expected '}' to close template substitution", token.span)
+                        .with_note(format!("found {:?}", token.kind)))
+                }
+            }
         }
     }
 
-    fn grouping(&mut self) -> Result<Expression, String> {
-        self.consume_token_type(TokenType::LeftParen, "expected '(' to start grouping")?;
-        let expr = self.expression()?;
-        self.consume_token_type(TokenType::RightParen, "expected ')' to close grouping")?;
+    fn grouping(&mut self) -> Result<Expression, Diagnostic> {
+        let start = self.consume_token_type(TokenType::LeftParen, "expected '(' to start grouping")?.span;
+        let expr = self.sequence()?;
+        let end = self.consume_token_type(TokenType::RightParen, "expected ')' to close grouping")?.span;
         Ok(Expression::Grouping {
             expression: Box::new(expr),
+            span: start.merge(end),
         })
     }
 
-    fn unary(&mut self) -> Result<Expression, String> {
+    fn unary(&mut self) -> Result<Expression, Diagnostic> {
         let operator_token = self.consume_token();
-        // workaround: cannot borrow `*self` as mutable more than once
-        let operator_token = Token::new(operator_token.kind.clone(), operator_token.line);
+        let (operator, start) = (operator_token.kind.clone(), operator_token.span);
         let factor = self.factor()?;
-
-        match operator_token.kind {
-            TokenType::Minus => Ok(Expression::Unary {
-                operator: TokenType::Minus,
-                right: Box::new(factor),
+        let span = start.merge(factor.span());
+
+        match operator {
+            TokenType::PlusPlus | TokenType::MinusMinus => Ok(Expression::Update {
+                operator,
+                prefix: true,
+                target: Box::new(self.simple_assignment_target(&factor)?),
+                span,
             }),
-            _ => Err(format!(
-                "line {}: Invalid unary operator {:?}",
-                operator_token.line, operator_token.kind
+            TokenType::Minus
+            | TokenType::Plus
+            | TokenType::Bang
+            | TokenType::Tilde
+            | TokenType::Typeof
+            | TokenType::Void
+            | TokenType::Delete => Ok(Expression::Unary { operator, right: Box::new(factor), span }),
+            _ => Err(Diagnostic::error(
+                "E0102",
+                format!("Invalid unary operator {:?}", operator),
+                start,
             )),
         }
     }
 
-    fn identifier(&mut self) -> Result<Expression, String> {
-        let token = self.consume_token();
+    /// The variable assigned by `=` or updated by `++`, parentheses around it are allowed.
+    fn simple_assignment_target(&self, target: &Expression) -> Result<Expression, Diagnostic> {
+        let mut simple_target = target;
+        while let Expression::Grouping { expression, .. } = simple_target {
+            simple_target = expression;
+        }
+        if !matches!(simple_target, Expression::Identifier { .. }) {
+            return Err(Diagnostic::error("E0105", "invalid assignment target", target.span())
+                .with_note("only variables can be assigned to"));
+        }
+
+        Ok(simple_target.clone())
+    }
+
+    /// Consumes an identifier, a token that isn't one is left for recovery.
+    fn identifier(&mut self) -> Result<Expression, Diagnostic> {
+        let token = self.peek();
         match token.kind {
-            TokenType::Identifier(ref name) => Ok(Expression::Identifier(String::from(name))),
-            _ => Err(format!(
-                "line {}: identifier expected instead of {:?}",
-                token.line, token.kind
+            TokenType::Identifier(ref name) => {
+                let identifier = Expression::Identifier { name: String::from(name), span: token.span };
+                self.consume_token();
+                Ok(identifier)
+            }
+            _ => Err(Diagnostic::error(
+                "E0101",
+                format!("identifier expected instead of {:?}", token.kind),
+                token.span,
             )),
         }
     }
 
+    /// Consumes the `;` that ends a statement, or inserts one the way ASI does:
+    /// before a token on a new line, before `}` and at the end of the input.
+    /// Returns the span the statement ends at, or `None` when neither applies.
+    fn end_of_statement(&mut self) -> Option<Span> {
+        let token = self.peek();
+        if token.kind == TokenType::Semicolon {
+            return Some(self.consume_token().span);
+        }
+
+        if token.newline_before || matches!(token.kind, TokenType::RightCurlyBrace | TokenType::Eof) {
+            return Some(self.previous().span);
+        }
+
+        None
+    }
+
+    fn expected_semicolon(&self, error_message: &str) -> Diagnostic {
+        let found = self.peek();
+        Diagnostic::error("E0101", error_message, found.span)
+            .with_note(format!("found {:?}", found.kind))
+    }
+
     fn consume_token_type(
         &mut self,
         token_type: TokenType,
         error_message: &str,
-    ) -> Result<&Token, String> {
+    ) -> Result<&Token, Diagnostic> {
         if self.is_end() || self.peek().kind != token_type {
-            return Err(String::from(error_message));
+            let found = self.peek();
+            return Err(Diagnostic::error("E0101", error_message, found.span)
+                .with_note(format!("found {:?}", found.kind)));
         }
 
         self.position += 1;
@@ -360,12 +1161,6 @@ impl Parser {
         self.previous()
     }
 
-    fn go_to_previous_token(&mut self) {
-        if self.position != 0 {
-            self.position -= 1;
-        }
-    }
-
     fn peek(&self) -> &Token {
         if self.is_end() {
             // TokenType::Eof is always present
@@ -375,6 +1170,13 @@ impl Parser {
         &self.tokens[self.position]
     }
 
+    fn peek_next(&self) -> &Token {
+        match self.tokens.get(self.position + 1) {
+            Some(token) => token,
+            None => &self.tokens[self.tokens.len() - 1],
+        }
+    }
+
     fn previous(&self) -> &Token {
         &self.tokens[self.position - 1]
     }
@@ -4,45 +4,51 @@ use crate::common::{*, ast::*};
 #[test]
 fn parse_literals() {
     let tokens = vec![
-        Token::new(TokenType::Literal(Literal::Number(5.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Literal(Literal::Boolean(true)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Literal(Literal::Boolean(false)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Literal(Literal::String("hello".into())), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Literal(Literal::Null), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Literal(Literal::Undefined), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Literal(Literal::Number(5.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Literal(Literal::Boolean(true)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Literal(Literal::Boolean(false)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Literal(Literal::String("hello".into())), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Literal(Literal::Null), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Literal(Literal::Undefined), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
+    assert!(errors.is_empty());
     assert_eq!(
-      result.unwrap(),
+      result,
       vec![
         Statement::ExpressionStatement {
-          expression: Box::new(Expression::Literal(Literal::Number(5.0)))
+          expression: Box::new(Expression::Literal { value: Literal::Number(5.0), span: Span::default() }),
+          span: Span::default()
         },
         Statement::ExpressionStatement {
-          expression: Box::new(Expression::Literal(Literal::Boolean(true)))
+          expression: Box::new(Expression::Literal { value: Literal::Boolean(true), span: Span::default() }),
+          span: Span::default()
         },
         Statement::ExpressionStatement {
-          expression: Box::new(Expression::Literal(Literal::Boolean(false)))
+          expression: Box::new(Expression::Literal { value: Literal::Boolean(false), span: Span::default() }),
+          span: Span::default()
         },
         Statement::ExpressionStatement {
-          expression: Box::new(Expression::Literal(Literal::String("hello".into())))
+          expression: Box::new(Expression::Literal { value: Literal::String("hello".into()), span: Span::default() }),
+          span: Span::default()
         },
         Statement::ExpressionStatement {
-          expression: Box::new(Expression::Literal(Literal::Null))
+          expression: Box::new(Expression::Literal { value: Literal::Null, span: Span::default() }),
+          span: Span::default()
         },
         Statement::ExpressionStatement {
-          expression: Box::new(Expression::Literal(Literal::Undefined))
+          expression: Box::new(Expression::Literal { value: Literal::Undefined, span: Span::default() }),
+          span: Span::default()
         },
       ]
     );
@@ -50,35 +56,37 @@ fn parse_literals() {
 
 #[test]
 fn parse_empty_program() {
-    let tokens = vec![Token::new(TokenType::Eof, 1)];
+    let tokens = vec![Token::new(TokenType::Eof, Span::default())];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![]);
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![]);
 }
 
 #[test]
 fn parse_binary_expression() {
     let tokens = vec![
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::Slash, 1),
-        Token::new(TokenType::Literal(Literal::Number(8.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::Slash, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(8.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
+    assert!(errors.is_empty());
     assert_eq!(
-        result.unwrap(),
+        result,
         vec![Statement::ExpressionStatement {
             expression: Box::new(Expression::Binary {
-                left: Box::new(Expression::Literal(Literal::Number(1.0))),
+                left: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
                 operator: TokenType::Slash,
-                right: Box::new(Expression::Literal(Literal::Number(8.0))),
-            }) 
+                right: Box::new(Expression::Literal { value: Literal::Number(8.0), span: Span::default() }),
+                span: Span::default(),
+            }),
+            span: Span::default()
         }]
     );
 }
@@ -86,35 +94,39 @@ fn parse_binary_expression() {
 #[test]
 fn parse_grouping() {
     let tokens = vec![
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::Plus, 1),
-        Token::new(TokenType::Literal(Literal::Number(8.0)), 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Star, 1),
-        Token::new(TokenType::Literal(Literal::Number(3.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::Plus, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(8.0)), Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Star, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(3.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
+    assert!(errors.is_empty());
     assert_eq!(
-        result.unwrap(),
+        result,
         vec![
           Statement::ExpressionStatement {
             expression: Box::new(Expression::Binary { 
                 left: Box::new(Expression::Grouping {
                     expression: Box::new(Expression::Binary {
-                        left: Box::new(Expression::Literal(Literal::Number(1.0))),
+                        left: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
                         operator: TokenType::Plus,
-                        right: Box::new(Expression::Literal(Literal::Number(8.0))),
+                        right: Box::new(Expression::Literal { value: Literal::Number(8.0), span: Span::default() }),
+                        span: Span::default(),
                     }),
+                    span: Span::default(),
                 }),
                 operator: TokenType::Star,
-                right: Box::new(Expression::Literal(Literal::Number(3.0)))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Number(3.0), span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
           }
         ]
     );
@@ -123,23 +135,24 @@ fn parse_grouping() {
 #[test]
 fn test_valid_declaration_with_initializer() {
     let tokens = vec![
-      Token::new(TokenType::KeywordLet, 1),
-      Token::new(TokenType::Identifier("x".into()), 1),
-      Token::new(TokenType::Assign, 1),
-      Token::new(TokenType::Literal(Literal::Number(5.0)), 1),
-      Token::new(TokenType::Semicolon, 1),
-      Token::new(TokenType::Eof, 1),
+      Token::new(TokenType::KeywordLet, Span::default()),
+      Token::new(TokenType::Identifier("x".into()), Span::default()),
+      Token::new(TokenType::Assign, Span::default()),
+      Token::new(TokenType::Literal(Literal::Number(5.0)), Span::default()),
+      Token::new(TokenType::Semicolon, Span::default()),
+      Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
+    assert!(errors.is_empty());
     assert_eq!(
-        result.unwrap(),
+        result,
         vec![Statement::Declaration {
             is_const: false,
             name: "x".into(),
-            value: Box::new(Some(Expression::Literal(Literal::Number(5.0))))
+            value: Box::new(Some(Expression::Literal { value: Literal::Number(5.0), span: Span::default() })),
+            span: Span::default()
         }]
     );
 }
@@ -147,21 +160,22 @@ fn test_valid_declaration_with_initializer() {
 #[test]
 fn test_valid_declaration_without_initializer() {
     let tokens = vec![
-      Token::new(TokenType::KeywordConst, 1),
-      Token::new(TokenType::Identifier("x".into()), 1),
-      Token::new(TokenType::Semicolon, 1),
-      Token::new(TokenType::Eof, 1),
+      Token::new(TokenType::KeywordLet, Span::default()),
+      Token::new(TokenType::Identifier("x".into()), Span::default()),
+      Token::new(TokenType::Semicolon, Span::default()),
+      Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
+    assert!(errors.is_empty());
     assert_eq!(
-        result.unwrap(),
+        result,
         vec![Statement::Declaration {
-            is_const: true,
+            is_const: false,
             name: "x".into(),
-            value: Box::new(None)
+            value: Box::new(None),
+            span: Span::default()
         }]
     );
 }
@@ -169,52 +183,55 @@ fn test_valid_declaration_without_initializer() {
 #[test]
 fn test_identifiers_as_right_hand_side_values() {
     let tokens = vec![
-      Token::new(TokenType::KeywordConst, 1),
-      Token::new(TokenType::Identifier("x".into()), 1),
-      Token::new(TokenType::Assign, 1),
-      Token::new(TokenType::Literal(Literal::Number(5.0)), 1),
-      Token::new(TokenType::Semicolon, 1),
-      Token::new(TokenType::KeywordLet, 1),
-      Token::new(TokenType::Identifier("y".into()), 1),
-      Token::new(TokenType::Assign, 1),
-      Token::new(TokenType::Identifier("x".into()), 1),
-      Token::new(TokenType::Semicolon, 1),
-      Token::new(TokenType::Eof, 1),
+      Token::new(TokenType::KeywordConst, Span::default()),
+      Token::new(TokenType::Identifier("x".into()), Span::default()),
+      Token::new(TokenType::Assign, Span::default()),
+      Token::new(TokenType::Literal(Literal::Number(5.0)), Span::default()),
+      Token::new(TokenType::Semicolon, Span::default()),
+      Token::new(TokenType::KeywordLet, Span::default()),
+      Token::new(TokenType::Identifier("y".into()), Span::default()),
+      Token::new(TokenType::Assign, Span::default()),
+      Token::new(TokenType::Identifier("x".into()), Span::default()),
+      Token::new(TokenType::Semicolon, Span::default()),
+      Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
 
-    assert!(result.is_ok());
+    assert!(errors.is_empty());
 }
 
  #[test]
 fn test_operator_precedence_multiplication() {
     let tokens = vec![
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::Plus, 1),
-        Token::new(TokenType::Literal(Literal::Number(2.0)), 1),
-        Token::new(TokenType::Star, 1),
-        Token::new(TokenType::Literal(Literal::Number(3.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::Plus, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(2.0)), Span::default()),
+        Token::new(TokenType::Star, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(3.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Binary {
-                left: Box::new(Expression::Literal(Literal::Number(1.0))),
+                left: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
                 operator: TokenType::Plus,
                 right: Box::new(
                     Expression::Binary { 
-                        left: Box::new(Expression::Literal(Literal::Number(2.0))),
+                        left: Box::new(Expression::Literal { value: Literal::Number(2.0), span: Span::default() }),
                         operator: TokenType::Star,
-                        right: Box::new(Expression::Literal(Literal::Number(3.0)))
+                        right: Box::new(Expression::Literal { value: Literal::Number(3.0), span: Span::default() }),
+                        span: Span::default()
                     }
-                )
-            })
+                ),
+                span: Span::default()
+            }),
+            span: Span::default()
         }
     ]);
 }
@@ -222,31 +239,34 @@ fn test_operator_precedence_multiplication() {
 #[test]
 fn test_operator_precedence_division() {
     let tokens = vec![
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::Minus, 1),
-        Token::new(TokenType::Literal(Literal::Number(10.0)), 1),
-        Token::new(TokenType::Slash, 1),
-        Token::new(TokenType::Literal(Literal::Number(2.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::Minus, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(10.0)), Span::default()),
+        Token::new(TokenType::Slash, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(2.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Binary {
-                left: Box::new(Expression::Literal(Literal::Number(1.0))),
+                left: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
                 operator: TokenType::Minus,
                 right: Box::new(
                     Expression::Binary { 
-                        left: Box::new(Expression::Literal(Literal::Number(10.0))),
+                        left: Box::new(Expression::Literal { value: Literal::Number(10.0), span: Span::default() }),
                         operator: TokenType::Slash,
-                    right: Box::new(Expression::Literal(Literal::Number(2.0)))
+                    right: Box::new(Expression::Literal { value: Literal::Number(2.0), span: Span::default() }),
+                        span: Span::default()
                     }
-                )
-            })
+                ),
+                span: Span::default()
+            }),
+            span: Span::default()
         }
     ]);
 }
@@ -254,33 +274,37 @@ fn test_operator_precedence_division() {
 #[test]
 fn test_operator_precedence_parentheses() {
     let tokens = vec![
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::Plus, 1),
-        Token::new(TokenType::Literal(Literal::Number(2.0)), 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Star, 1),
-        Token::new(TokenType::Literal(Literal::Number(3.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::Plus, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(2.0)), Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Star, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(3.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Binary {
                 left: Box::new(Expression::Grouping {
                     expression: Box::new(Expression::Binary { 
-                        left: Box::new(Expression::Literal(Literal::Number(1.0))),
+                        left: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
                         operator: TokenType::Plus,
-                        right: Box::new(Expression::Literal(Literal::Number(2.0)))
-                    })},
+                        right: Box::new(Expression::Literal { value: Literal::Number(2.0), span: Span::default() }),
+                        span: Span::default()
+                    }),
+                    span: Span::default()},
                 ),
                 operator: TokenType::Star,
-                right: Box::new(Expression::Literal(Literal::Number(3.0)))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Number(3.0), span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         }
     ]);
 }
@@ -288,115 +312,131 @@ fn test_operator_precedence_parentheses() {
 #[test]
 fn test_comparison_operators() {
     let tokens = vec![
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::Equal, 1),
-        Token::new(TokenType::Literal(Literal::Number(2.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-
-        Token::new(TokenType::Literal(Literal::String("hello".into())), 1),
-        Token::new(TokenType::NotEqual, 1),
-        Token::new(TokenType::Literal(Literal::Number(4.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::Equal, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(2.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+
+        Token::new(TokenType::Literal(Literal::String("hello".into())), Span::default()),
+        Token::new(TokenType::NotEqual, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(4.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
         
-        Token::new(TokenType::Literal(Literal::Boolean(false)), 1),
-        Token::new(TokenType::StrictEqual, 1),
-        Token::new(TokenType::Literal(Literal::Boolean(true)), 1),
-        Token::new(TokenType::Semicolon, 1),
+        Token::new(TokenType::Literal(Literal::Boolean(false)), Span::default()),
+        Token::new(TokenType::StrictEqual, Span::default()),
+        Token::new(TokenType::Literal(Literal::Boolean(true)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
         
-        Token::new(TokenType::Literal(Literal::Boolean(false)), 1),
-        Token::new(TokenType::StrictNotEqual, 1),
-        Token::new(TokenType::Literal(Literal::Number(2.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
-
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::GreaterThan, 1),
-        Token::new(TokenType::Literal(Literal::Number(2.0)), 1),
-        Token::new(TokenType::Semicolon, 1),
+        Token::new(TokenType::Literal(Literal::Boolean(false)), Span::default()),
+        Token::new(TokenType::StrictNotEqual, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(2.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::GreaterThan, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(2.0)), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
         
-        Token::new(TokenType::Literal(Literal::Null), 1),
-        Token::new(TokenType::LessThan, 1),
-        Token::new(TokenType::Literal(Literal::Undefined), 1),
-        Token::new(TokenType::Semicolon, 1),
+        Token::new(TokenType::Literal(Literal::Null), Span::default()),
+        Token::new(TokenType::LessThan, Span::default()),
+        Token::new(TokenType::Literal(Literal::Undefined), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
         
-        Token::new(TokenType::Literal(Literal::Boolean(false)), 1),
-        Token::new(TokenType::GreaterThanOrEqual, 1),
-        Token::new(TokenType::Literal(Literal::Undefined), 1),
-        Token::new(TokenType::Semicolon, 1),
+        Token::new(TokenType::Literal(Literal::Boolean(false)), Span::default()),
+        Token::new(TokenType::GreaterThanOrEqual, Span::default()),
+        Token::new(TokenType::Literal(Literal::Undefined), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
         
-        Token::new(TokenType::Literal(Literal::String("hello".into())), 1),
-        Token::new(TokenType::LessThanOrEqual, 1),
-        Token::new(TokenType::Literal(Literal::Null), 1),
-        Token::new(TokenType::Semicolon, 1),
+        Token::new(TokenType::Literal(Literal::String("hello".into())), Span::default()),
+        Token::new(TokenType::LessThanOrEqual, Span::default()),
+        Token::new(TokenType::Literal(Literal::Null), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
 
-        Token::new(TokenType::Eof, 1)
+        Token::new(TokenType::Eof, Span::default())
     ];
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::Number(1.0))),
+                left: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
                 operator: TokenType::Equal,
-                right: Box::new(Expression::Literal(Literal::Number(2.0)))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Number(2.0), span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         },
 
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::String("hello".into()))),
+                left: Box::new(Expression::Literal { value: Literal::String("hello".into()), span: Span::default() }),
                 operator: TokenType::NotEqual,
-                right: Box::new(Expression::Literal(Literal::Number(4.0)))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Number(4.0), span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         },
         
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::Boolean(false))),
+                left: Box::new(Expression::Literal { value: Literal::Boolean(false), span: Span::default() }),
                 operator: TokenType::StrictEqual,
-                right: Box::new(Expression::Literal(Literal::Boolean(true)))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Boolean(true), span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         },
         
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::Boolean(false))),
+                left: Box::new(Expression::Literal { value: Literal::Boolean(false), span: Span::default() }),
                 operator: TokenType::StrictNotEqual,
-                right: Box::new(Expression::Literal(Literal::Number(2.0)))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Number(2.0), span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         },
         
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::Number(1.0))),
+                left: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
                 operator: TokenType::GreaterThan,
-                right: Box::new(Expression::Literal(Literal::Number(2.0)))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Number(2.0), span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         },
         
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::Null)),
+                left: Box::new(Expression::Literal { value: Literal::Null, span: Span::default() }),
                 operator: TokenType::LessThan,
-                right: Box::new(Expression::Literal(Literal::Undefined))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Undefined, span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         },
 
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::Boolean(false))),
+                left: Box::new(Expression::Literal { value: Literal::Boolean(false), span: Span::default() }),
                 operator: TokenType::GreaterThanOrEqual,
-                right: Box::new(Expression::Literal(Literal::Undefined))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Undefined, span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         },
         
         Statement::ExpressionStatement { 
             expression: Box::new(Expression::Comparison { 
-                left: Box::new(Expression::Literal(Literal::String("hello".into()))),
+                left: Box::new(Expression::Literal { value: Literal::String("hello".into()), span: Span::default() }),
                 operator: TokenType::LessThanOrEqual,
-                right: Box::new(Expression::Literal(Literal::Null))
-            })
+                right: Box::new(Expression::Literal { value: Literal::Null, span: Span::default() }),
+                span: Span::default()
+            }),
+            span: Span::default()
         }
     ]);
 }
@@ -404,31 +444,33 @@ fn test_comparison_operators() {
 #[test]
 fn test_function_declaration_no_params() {
     let tokens = vec![
-        Token::new(TokenType::Function, 1),
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::LeftCurlyBrace, 1),
-        Token::new(TokenType::Return, 1),
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::RightCurlyBrace, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Function, Span::default()),
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::LeftCurlyBrace, Span::default()),
+        Token::new(TokenType::Return, Span::default()),
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::RightCurlyBrace, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::FunctionDeclaration {
             name: "hello".into(),
             params: vec![],
-            body: Box::new(vec![
+            body: vec![
                 Statement::Return  {
-                    expression: Box::new(Expression::Identifier("hello".into())) 
+                    expression: Box::new(Expression::Identifier { name: "hello".into(), span: Span::default() }),
+                    span: Span::default()
                 }
-            ])
+            ],
+            span: Span::default()
         }
     ]);
 }
@@ -436,28 +478,30 @@ fn test_function_declaration_no_params() {
 #[test]
 fn test_function_declaration_empty_body() {
     let tokens = vec![
-        Token::new(TokenType::Function, 1),
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::LeftCurlyBrace, 1),
-        Token::new(TokenType::RightCurlyBrace, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Function, Span::default()),
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::LeftCurlyBrace, Span::default()),
+        Token::new(TokenType::RightCurlyBrace, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::FunctionDeclaration {
             name: "hello".into(),
             params: vec![],
-            body: Box::new(vec![
+            body: vec![
                 Statement::Return { 
-                    expression: Box::new(Expression::Literal(Literal::Undefined))
+                    expression: Box::new(Expression::Literal { value: Literal::Undefined, span: Span::default() }),
+                    span: Span::default()
                 }
-            ])
+            ],
+            span: Span::default()
         }
     ]);
 }
@@ -465,28 +509,29 @@ fn test_function_declaration_empty_body() {
 #[test]
 fn test_function_declaration_with_return_nothing() {
     let tokens = vec![
-        Token::new(TokenType::Function, 1),
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::LeftCurlyBrace, 1),
-        Token::new(TokenType::Return, 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::RightCurlyBrace, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Function, Span::default()),
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::LeftCurlyBrace, Span::default()),
+        Token::new(TokenType::Return, Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::RightCurlyBrace, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::FunctionDeclaration {
             name: "hello".into(),
             params: vec![],
-            body: Box::new(vec![
-                Statement::Return { expression: Box::new(Expression::Literal(Literal::Undefined)) }
-            ])
+            body: vec![
+                Statement::Return { expression: Box::new(Expression::Literal { value: Literal::Undefined, span: Span::default() }), span: Span::default() }
+            ],
+            span: Span::default()
         }
     ]);
 }
@@ -494,39 +539,42 @@ fn test_function_declaration_with_return_nothing() {
 #[test]
 fn test_function_declaration_with_params() {
     let tokens = vec![
-        Token::new(TokenType::Function, 1),
-        Token::new(TokenType::Identifier("add".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::Identifier("x".into()), 1),
-        Token::new(TokenType::Comma, 1),
-        Token::new(TokenType::Identifier("y".into()), 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::LeftCurlyBrace, 1),
-        Token::new(TokenType::Return, 1),
-        Token::new(TokenType::Identifier("x".into()), 1),
-        Token::new(TokenType::Plus, 1),
-        Token::new(TokenType::Identifier("y".into()), 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::RightCurlyBrace, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Function, Span::default()),
+        Token::new(TokenType::Identifier("add".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::Identifier("x".into()), Span::default()),
+        Token::new(TokenType::Comma, Span::default()),
+        Token::new(TokenType::Identifier("y".into()), Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::LeftCurlyBrace, Span::default()),
+        Token::new(TokenType::Return, Span::default()),
+        Token::new(TokenType::Identifier("x".into()), Span::default()),
+        Token::new(TokenType::Plus, Span::default()),
+        Token::new(TokenType::Identifier("y".into()), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::RightCurlyBrace, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
         Statement::FunctionDeclaration {
             name: "add".into(),
             params: vec!["x".into(), "y".into()],
-            body: Box::new(vec![
+            body: vec![
                 Statement::Return { 
                     expression: Box::new(Expression::Binary { 
-                        left: Box::new(Expression::Identifier("x".into())),
+                        left: Box::new(Expression::Identifier { name: "x".into(), span: Span::default() }),
                         operator: TokenType::Plus,
-                        right: Box::new(Expression::Identifier("y".into()))
-                    })}
-                ])
+                        right: Box::new(Expression::Identifier { name: "y".into(), span: Span::default() }),
+                        span: Span::default()
+                    }),
+                    span: Span::default()}
+                ],
+            span: Span::default()
             }
         ]
     );
@@ -535,131 +583,138 @@ fn test_function_declaration_with_params() {
 #[test]
 fn test_function_declaration_with_invalid_syntax() {
     let tokens = vec![
-        Token::new(TokenType::Function, 1),
-        Token::new(TokenType::Identifier("get".into()), 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Return, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Function, Span::default()),
+        Token::new(TokenType::Identifier("get".into()), Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Return, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
 
-    assert!(result.is_err());
+    assert!(!errors.is_empty());
     
     let tokens = vec![
-        Token::new(TokenType::Function, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Function, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
 
-    assert!(result.is_err());
+    assert!(!errors.is_empty());
 
     let tokens = vec![
-        Token::new(TokenType::Function, 1),
-        Token::new(TokenType::Identifier("add".into()), 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Comma, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::LeftCurlyBrace, 1),
-        Token::new(TokenType::RightCurlyBrace, 1),
-        Token::new(TokenType::Eof, 1),
+        Token::new(TokenType::Function, Span::default()),
+        Token::new(TokenType::Identifier("add".into()), Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Comma, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::LeftCurlyBrace, Span::default()),
+        Token::new(TokenType::RightCurlyBrace, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
 
-    assert!(result.is_err());
+    assert!(!errors.is_empty());
 }
 
 #[test]
 fn test_valid_function_calls() {
     let tokens = vec![
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1)
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default())
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![Statement::ExpressionStatement {
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![Statement::ExpressionStatement {
             expression: Box::new(Expression::Call {
-                callee: "hello".into(),
-                args: Box::new(vec![])
-            })
+                callee: Box::new(Expression::Identifier { name: "hello".into(), span: Span::default() }),
+                args: vec![],
+                span: Span::default()
+            }),
+            span: Span::default()
         }
     ]);
     
     let tokens = vec![
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 2),
-        Token::new(TokenType::Identifier("name".into()), 3),
-        Token::new(TokenType::Comma, 4),
-        Token::new(TokenType::Identifier("surname".into()), 5),
-        Token::new(TokenType::RightParen, 6),
-        Token::new(TokenType::Semicolon, 7),
-        Token::new(TokenType::Eof, 8)
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::Identifier("name".into()), Span::default()),
+        Token::new(TokenType::Comma, Span::default()),
+        Token::new(TokenType::Identifier("surname".into()), Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default())
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![Statement::ExpressionStatement {
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![Statement::ExpressionStatement {
             expression: Box::new(Expression::Call {
-                callee: "hello".into(),
-                args: Box::new(vec![
-                    Expression::Identifier("name".into()),
-                    Expression::Identifier("surname".into()),
-                ])
-            })
+                callee: Box::new(Expression::Identifier { name: "hello".into(), span: Span::default() }),
+                args: vec![
+                    Expression::Identifier { name: "name".into(), span: Span::default() },
+                    Expression::Identifier { name: "surname".into(), span: Span::default() },
+                ],
+                span: Span::default()
+            }),
+            span: Span::default()
         }
     ]);
     
     let tokens = vec![
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
 
-        Token::new(TokenType::Identifier("name".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Comma, 1),
+        Token::new(TokenType::Identifier("name".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Comma, Span::default()),
 
-        Token::new(TokenType::Literal(Literal::Number(1.0)), 1),
-        Token::new(TokenType::Comma, 1),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::Comma, Span::default()),
 
-        Token::new(TokenType::Literal(Literal::String("surname".into())), 1),
+        Token::new(TokenType::Literal(Literal::String("surname".into())), Span::default()),
 
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1)
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default())
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (result, errors) = parser.parse();
 
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), vec![Statement::ExpressionStatement {
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![Statement::ExpressionStatement {
             expression: Box::new(Expression::Call {
-                callee: "hello".into(),
-                args: Box::new(vec![
+                callee: Box::new(Expression::Identifier { name: "hello".into(), span: Span::default() }),
+                args: vec![
                     Expression::Call {
-                        callee: "name".into(),
-                        args: Box::new(vec![]),
+                        callee: Box::new(Expression::Identifier { name: "name".into(), span: Span::default() }),
+                        args: vec![],
+                        span: Span::default(),
                     },
-                    Expression::Literal(Literal::Number(1.0)),
-                    Expression::Literal(Literal::String("surname".into()))
-                ])
-            })
+                    Expression::Literal { value: Literal::Number(1.0), span: Span::default() },
+                    Expression::Literal { value: Literal::String("surname".into()), span: Span::default() }
+                ],
+                span: Span::default()
+            }),
+            span: Span::default()
         }
     ]);
 }
@@ -667,53 +722,875 @@ fn test_valid_function_calls() {
 #[test]
 fn test_invalid_function_calls() {
     let tokens = vec![
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Eof, 1)
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::Eof, Span::default())
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
 
-    assert!(result.is_err());
+    assert!(!errors.is_empty());
     
     let tokens = vec![
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::Semicolon, 1),
-        Token::new(TokenType::Eof, 1)
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default())
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
 
-    assert!(result.is_err());
+    assert!(!errors.is_empty());
     
     let tokens = vec![
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::Comma, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Eof, 1)
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::Comma, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Eof, Span::default())
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
 
-    assert!(result.is_err());
+    assert!(!errors.is_empty());
     
     let tokens = vec![
-        Token::new(TokenType::Identifier("hello".into()), 1),
-        Token::new(TokenType::LeftParen, 1),
-        Token::new(TokenType::Literal(Literal::Boolean(true)), 1),
-        Token::new(TokenType::Comma, 1),
-        Token::new(TokenType::RightParen, 1),
-        Token::new(TokenType::Eof, 1)
+        Token::new(TokenType::Identifier("hello".into()), Span::default()),
+        Token::new(TokenType::LeftParen, Span::default()),
+        Token::new(TokenType::Literal(Literal::Boolean(true)), Span::default()),
+        Token::new(TokenType::Comma, Span::default()),
+        Token::new(TokenType::RightParen, Span::default()),
+        Token::new(TokenType::Eof, Span::default())
+    ];
+
+    let mut parser = Parser::new(tokens);
+    let (_, errors) = parser.parse();
+
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn test_node_spans() {
+    let mut tokenizer = crate::tokenizer::Tokenizer::new("let x = (1 + 2) * y;\nadd(x, 1);".into());
+    let mut parser = Parser::new(tokenizer.tokenize().unwrap());
+    let (result, errors) = parser.parse();
+    assert!(errors.is_empty());
+
+    assert_eq!(result[0].span(), Span::new(0, 20, 1, 1));
+    match &result[0] {
+        Statement::Declaration { value, .. } => {
+            let value = value.as_ref().as_ref().unwrap();
+            assert_eq!(value.span(), Span::new(8, 19, 1, 9));
+            match value {
+                Expression::Binary { left, right, .. } => {
+                    assert_eq!(left.span(), Span::new(8, 15, 1, 9));
+                    assert_eq!(right.span(), Span::new(18, 19, 1, 19));
+                }
+                _ => panic!("expected binary expression"),
+            }
+        }
+        _ => panic!("expected declaration"),
+    }
+
+    assert_eq!(result[1].span(), Span::new(21, 31, 2, 1));
+    match &result[1] {
+        Statement::ExpressionStatement { expression, .. } => {
+            assert_eq!(expression.span(), Span::new(21, 30, 2, 1));
+        }
+        _ => panic!("expected expression statement"),
+    }
+}
+
+#[test]
+fn test_error_diagnostics() {
+    let mut tokenizer = crate::tokenizer::Tokenizer::new("let x = 5 let y = 2;".into());
+    let mut parser = Parser::new(tokenizer.tokenize().unwrap());
+    let (_, errors) = parser.parse();
+    let error = &errors[0];
+
+    assert_eq!(error.code, "E0101");
+    assert_eq!(error.message, "expected ';' after declaration");
+    assert_eq!(error.span, Span::new(10, 13, 1, 11));
+    assert_eq!(error.notes, vec![String::from("found KeywordLet")]);
+}
+
+#[test]
+fn test_recovery_reports_every_error() {
+    let source = "let a = ;\nlet b = 1;\nlet c = 2 const d = 3;\nfoo(1,;\nlet e = 4;";
+    let mut tokenizer = crate::tokenizer::Tokenizer::new(source.into());
+    let mut parser = Parser::new(tokenizer.tokenize().unwrap());
+    let (result, errors) = parser.parse();
+
+    assert_eq!(
+        errors.iter().map(|e| e.span.line).collect::<Vec<usize>>(),
+        vec![1, 3, 4]
+    );
+    assert_eq!(
+        result.iter().filter_map(|statement| match statement {
+            Statement::Declaration { name, .. } => Some(name.as_str()),
+            _ => None,
+        }).collect::<Vec<&str>>(),
+        vec!["b", "d", "e"]
+    );
+}
+
+#[test]
+fn test_recovery_inside_function_body() {
+    let source = "function f() {\n  let x = * 2;\n  return 1;\n}\nlet y = );\nf();";
+    let mut tokenizer = crate::tokenizer::Tokenizer::new(source.into());
+    let mut parser = Parser::new(tokenizer.tokenize().unwrap());
+    let (result, errors) = parser.parse();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].span.line, 2);
+    assert_eq!(errors[1].span.line, 5);
+
+    assert_eq!(result.len(), 2);
+    match &result[0] {
+        Statement::FunctionDeclaration { body, .. } => {
+            assert!(matches!(body[..], [Statement::Return { .. }]));
+        }
+        _ => panic!("expected function declaration"),
+    }
+    assert!(matches!(result[1], Statement::ExpressionStatement { .. }));
+}
+
+#[test]
+fn test_recovery_on_stray_tokens() {
+    let source = "} ) let x = 1; }";
+    let mut tokenizer = crate::tokenizer::Tokenizer::new(source.into());
+    let mut parser = Parser::new(tokenizer.tokenize().unwrap());
+    let (result, errors) = parser.parse();
+
+    // `}` and `)` are skipped together, the trailing `}` is reported on its own
+    assert_eq!(errors.len(), 2);
+    assert_eq!(result.len(), 1);
+}
+
+#[test]
+fn test_recovery_skips_the_block_of_a_broken_statement() {
+    for source in ["if (x { y; }", "function f( { }", "while (a { if (b) { c; } d; } let e = 1;"] {
+        let (result, errors) = parse_source(source);
+        assert_eq!(errors.len(), 1, "{}: {:?}", source, errors);
+        assert!(result.iter().all(|statement| matches!(statement, Statement::Declaration { .. })), "{}", source);
+    }
+
+    let (result, errors) = parse_source("function f( { let x = ; } let y = );");
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(result.is_empty());
+}
+
+#[test]
+fn test_template_literal() {
+    let tokens = vec![
+        Token::new(TokenType::TemplateHead("a".into()), Span::default()),
+        Token::new(TokenType::Identifier("x".into()), Span::default()),
+        Token::new(TokenType::TemplateMiddle("b".into()), Span::default()),
+        Token::new(TokenType::Identifier("y".into()), Span::default()),
+        Token::new(TokenType::GreaterThan, Span::default()),
+        Token::new(TokenType::Literal(Literal::Number(1.0)), Span::default()),
+        Token::new(TokenType::TemplateTail("c".into()), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::NoSubstitutionTemplate("plain".into()), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
+    ];
+
+    let mut parser = Parser::new(tokens);
+    let (result, errors) = parser.parse();
+
+    assert!(errors.is_empty());
+    assert_eq!(result, vec![
+        Statement::ExpressionStatement {
+            expression: Box::new(Expression::Template {
+                quasis: vec!["a".into(), "b".into(), "c".into()],
+                expressions: vec![
+                    Expression::Identifier { name: "x".into(), span: Span::default() },
+                    Expression::Comparison {
+                        left: Box::new(Expression::Identifier { name: "y".into(), span: Span::default() }),
+                        operator: TokenType::GreaterThan,
+                        right: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::default() }),
+                        span: Span::default(),
+                    },
+                ],
+                span: Span::default(),
+            }),
+            span: Span::default(),
+        },
+        Statement::ExpressionStatement {
+            expression: Box::new(Expression::Template {
+                quasis: vec!["plain".into()],
+                expressions: vec![],
+                span: Span::default(),
+            }),
+            span: Span::default(),
+        },
+    ]);
+}
+
+#[test]
+fn test_unclosed_template_substitution() {
+    let tokens = vec![
+        Token::new(TokenType::TemplateHead("a".into()), Span::default()),
+        Token::new(TokenType::Identifier("x".into()), Span::default()),
+        Token::new(TokenType::Semicolon, Span::default()),
+        Token::new(TokenType::Eof, Span::default()),
     ];
 
     let mut parser = Parser::new(tokens);
-    let result = parser.parse();
+    let (_, errors) = parser.parse();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "expected '}' to close template substitution");
+}
+
+fn parse_source(source: &str) -> (Vec<Statement>, Vec<crate::common::Diagnostic>) {
+    let mut tokenizer = crate::tokenizer::Tokenizer::new(source.into());
+    Parser::new(tokenizer.tokenize().unwrap()).parse()
+}
+
+#[test]
+fn test_automatic_semicolon_insertion() {
+    let (result, errors) = parse_source("let a = 1\nconst b = a\nadd(a, b)\nfunction f() { return a }\nlet c");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(result.len(), 5);
+
+    // the inserted semicolon ends the statement at its last token
+    assert_eq!(result[0].span(), Span::new(0, 9, 1, 1));
+    assert_eq!(result[4].span(), Span::new(58, 63, 5, 1));
+}
+
+#[test]
+fn test_no_semicolon_insertion_when_the_statement_continues() {
+    let (result, errors) = parse_source("let a = 1\n+ 2\nlet b = f\n(1)");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(result.len(), 2);
+    match &result[1] {
+        Statement::Declaration { value, .. } => {
+            assert!(matches!(value.as_ref(), Some(Expression::Call { .. })))
+        }
+        _ => panic!("expected declaration"),
+    }
+}
+
+#[test]
+fn test_no_semicolon_insertion_on_the_same_line() {
+    let (_, errors) = parse_source("let a = 1 let b = 2");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "expected ';' after declaration");
+}
+
+#[test]
+fn test_return_followed_by_newline_returns_undefined() {
+    let (result, errors) = parse_source("function f() {\n  return\n  1 + 2\n}");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::FunctionDeclaration { body, .. } => {
+            assert!(matches!(
+                &body[0],
+                Statement::Return { expression, .. }
+                    if matches!(expression.as_ref(), Expression::Literal { value: Literal::Undefined, .. })
+            ));
+            assert!(matches!(&body[1], Statement::ExpressionStatement { .. }));
+        }
+        _ => panic!("expected function declaration"),
+    }
+}
+
+#[test]
+fn test_assignment_is_right_associative() {
+    let (result, errors) = parse_source("a = b += 1;");
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let identifier = |name: &str, span| Box::new(Expression::Identifier { name: name.into(), span });
+    assert_eq!(
+        result[0],
+        Statement::ExpressionStatement {
+            expression: Box::new(Expression::Assignment {
+                target: identifier("a", Span::new(0, 1, 1, 1)),
+                operator: TokenType::Assign,
+                value: Box::new(Expression::Assignment {
+                    target: identifier("b", Span::new(4, 5, 1, 5)),
+                    operator: TokenType::PlusAssign,
+                    value: Box::new(Expression::Literal { value: Literal::Number(1.0), span: Span::new(9, 10, 1, 10) }),
+                    span: Span::new(4, 10, 1, 5),
+                }),
+                span: Span::new(0, 10, 1, 1),
+            }),
+            span: Span::new(0, 11, 1, 1),
+        }
+    );
+}
+
+#[test]
+fn test_assignment_has_lower_precedence_than_comparison() {
+    let (result, errors) = parse_source("x = 1 + 2 > 2;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Assignment { value, .. } => assert!(matches!(value.as_ref(), Expression::Comparison { .. })),
+            other => panic!("expected assignment but got {:?}", other),
+        },
+        _ => panic!("expected expression statement"),
+    }
+}
+
+#[test]
+fn test_invalid_assignment_targets() {
+    for source in ["1 = 2;", "a + b = 2;", "f() += 1;"] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors.len(), 1, "{}", source);
+        assert_eq!(errors[0].code, "E0105", "{}", source);
+    }
+
+    let (_, errors) = parse_source("(a) = 2;");
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_if_else_statement() {
+    let (result, errors) = parse_source("if (a) { b; } else if (c) d; else e;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].span(), Span::new(0, 36, 1, 1));
+
+    match &result[0] {
+        Statement::If { test, consequent, alternate: Some(alternate), .. } => {
+            assert!(matches!(test.as_ref(), Expression::Identifier { name, .. } if name == "a"));
+            assert!(matches!(consequent.as_ref(), Statement::Block { body, .. } if body.len() == 1));
+            assert!(matches!(
+                alternate.as_ref(),
+                Statement::If { alternate: Some(_), .. }
+            ));
+        }
+        other => panic!("expected if statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_dangling_else_belongs_to_nearest_if() {
+    let (result, errors) = parse_source("if (a) if (b) c; else d;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::If { consequent, alternate: None, .. } => {
+            assert!(matches!(consequent.as_ref(), Statement::If { alternate: Some(_), .. }))
+        }
+        other => panic!("expected if statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_blocks() {
+    let (result, errors) = parse_source("{ let a = 1; { a; } }\n{}");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(result.len(), 2);
+    match &result[0] {
+        Statement::Block { body, .. } => {
+            assert!(matches!(body[0], Statement::Declaration { .. }));
+            assert!(matches!(body[1], Statement::Block { .. }));
+        }
+        other => panic!("expected block but got {:?}", other),
+    }
+    assert_eq!(result[1], Statement::Block { body: vec![], span: Span::new(22, 24, 2, 1) });
+}
+
+#[test]
+fn test_declaration_is_not_allowed_as_if_branch() {
+    let (_, errors) = parse_source("if (a) let b = 1;");
+    assert_eq!(errors[0].code, "E0103");
+
+    let (_, errors) = parse_source("if (a) { let b = 1; }");
+    assert!(errors.is_empty());
+}
 
-    assert!(result.is_err());
+#[test]
+fn test_nested_function_declarations() {
+    let (result, errors) = parse_source("function f() { function g() { if (a) { function h() {} } } }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Statement::FunctionDeclaration { body, .. } = &result[0] else {
+        panic!("expected a function but got {:?}", result[0]);
+    };
+    assert!(matches!(&body[0], Statement::FunctionDeclaration { name, .. } if name == "g"));
+}
+
+#[test]
+fn test_redeclaration_in_the_same_scope() {
+    for source in [
+        "let x = 1; let x = 2;",
+        "{ let x = 1; const x = 2; }",
+        "switch (a) { case 1: let x = 1; default: let x = 2; }",
+        "function f(x) { let x = 1; }",
+        "for (let i = 0; i < 1; i++) { let j; } let j; let j;",
+        "try {} catch (x) { let x; }",
+    ] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors.len(), 1, "{}: {:?}", source, errors);
+        assert_eq!(errors[0].code, "E0109", "{}", source);
+        assert_eq!(errors[0].message, format!("variable {} already declared", if source.starts_with("for") { "j" } else { "x" }));
+    }
+
+    for source in [
+        "let x = 1; { let x = 2; { let x = 3; } }",
+        "for (let x of a) { let x = 1; } let x;",
+        "function f(x) { { let x; } function g() { let x; } }",
+        "try {} catch (e) { { let e; } } let e;",
+        "for (const c of s) {}",
+    ] {
+        let (_, errors) = parse_source(source);
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+    }
+
+    for source in ["const x;", "{ const x }", "for (const i; i < 1;) {}"] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors[0].code, "E0103", "{}", source);
+        assert_eq!(errors[0].message, "Missing initializer in const declaration", "{}", source);
+    }
+}
+
+#[test]
+fn test_global_constants_cannot_be_redeclared() {
+    for source in ["let NaN = 5; NaN = 6;", "const Infinity = 1;"] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors.len(), 1, "{}: {:?}", source, errors);
+        assert_eq!(errors[0].code, "E0109", "{}", source);
+        assert!(errors[0].notes[0].ends_with("is a global constant"), "{}", source);
+    }
+
+    // they can be shadowed like any other variable
+    let (_, errors) = parse_source("{ let NaN = 5; } function f(Infinity) { let NaN; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_return_outside_of_functions() {
+    for source in ["return 1;", "if (a) { return; }", "try { return 1; } catch (e) {}", "function f() {} return;"] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors.len(), 1, "{}", source);
+        assert_eq!(errors[0].code, "E0110", "{}", source);
+    }
+
+    let (_, errors) = parse_source("function f() { if (a) { while (b) { return 1; } } function g() { return; } }");
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_loop_statements() {
+    let (result, errors) = parse_source("while (a) b;\ndo { c } while (d) e;\nfor (let i = 0; i < 3; i += 1) {}\nfor (;;) break;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert!(matches!(result[0], Statement::While { .. }));
+    // the `;` after a do-while is inserted even before a token on the same line
+    assert!(matches!(result[1], Statement::DoWhile { .. }));
+    assert!(matches!(result[2], Statement::ExpressionStatement { .. }));
+    match &result[3] {
+        Statement::For { init: Some(init), test: Some(_), update: Some(_), .. } => {
+            assert!(matches!(init.as_ref(), Statement::Declaration { name, .. } if name == "i"))
+        }
+        other => panic!("expected for statement but got {:?}", other),
+    }
+    assert!(matches!(
+        &result[4],
+        Statement::For { init: None, test: None, update: None, body, .. } if matches!(body.as_ref(), Statement::Break { label: None, .. })
+    ));
+}
+
+#[test]
+fn test_no_semicolon_insertion_in_for_head() {
+    let (_, errors) = parse_source("for (let i = 0\ni < 3\ni += 1) {}");
+    assert_eq!(errors[0].message, "expected ';' after for initializer");
+}
+
+#[test]
+fn test_labeled_statements() {
+    let (result, errors) = parse_source("outer: inner: for (;;) { while (a) { continue outer; } break inner; }\nblock: { break block; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::Labeled { label, body, .. } => {
+            assert_eq!(label, "outer");
+            assert!(matches!(body.as_ref(), Statement::Labeled { label, .. } if label == "inner"));
+        }
+        other => panic!("expected labeled statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_break_label_on_next_line_is_not_a_label() {
+    let (result, errors) = parse_source("a: while (x) { break\na }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::Labeled { body, .. } => match body.as_ref() {
+            Statement::While { body, .. } => match body.as_ref() {
+                Statement::Block { body, .. } => {
+                    assert!(matches!(body[0], Statement::Break { label: None, .. }));
+                    assert!(matches!(body[1], Statement::ExpressionStatement { .. }));
+                }
+                other => panic!("expected block but got {:?}", other),
+            },
+            other => panic!("expected while but got {:?}", other),
+        },
+        other => panic!("expected labeled statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_jumps() {
+    for (source, message) in [
+        ("break;", "break must be inside of a loop or switch"),
+        ("continue;", "continue must be inside of a loop"),
+        ("while (a) { break b; }", "undefined label 'b'"),
+        ("a: { continue a; }", "label 'a' does not label a loop"),
+        ("a: a: while (b) {}", "label 'a' is already declared"),
+        ("while (a) { function f() { break; } }", "break must be inside of a loop or switch"),
+        ("switch (a) { case 1: continue; }", "continue must be inside of a loop"),
+    ] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors[0].code, "E0106", "{}", source);
+        assert_eq!(errors[0].message, message, "{}", source);
+    }
+}
+
+#[test]
+fn test_for_in_and_for_of() {
+    let (result, errors) = parse_source("for (const c of 'abc') {}\nfor (let i in text) continue;\nfor (x of y) break;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ForOf { left, .. } => {
+            assert!(matches!(left.as_ref(), Statement::Declaration { is_const: true, name, .. } if name == "c"))
+        }
+        other => panic!("expected for...of but got {:?}", other),
+    }
+    assert!(matches!(result[1], Statement::ForIn { .. }));
+    match &result[2] {
+        Statement::ForOf { left, .. } => assert!(matches!(left.as_ref(), Statement::ExpressionStatement { .. })),
+        other => panic!("expected for...of but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_for_in_and_for_of_heads() {
+    let (_, errors) = parse_source("for (let c = 1 of 'abc') {}");
+    assert_eq!(errors[0].code, "E0103");
+    assert_eq!(errors[0].message, "'c' can't have an initializer here");
+
+    let (_, errors) = parse_source("for (a + 1 in b) {}");
+    assert_eq!(errors[0].code, "E0105");
+
+    for (source, start) in [("for (const [a, b] of pairs) {}", 11), ("for (let { key } in map) {}", 9), ("for ([a] of b) {}", 5)] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors[0].code, "E0112", "{}", source);
+        assert_eq!(errors[0].message, "destructuring patterns are not supported", "{}", source);
+        assert_eq!(errors[0].span.start, start, "{}", source);
+    }
+}
+
+#[test]
+fn test_switch_statement() {
+    let (result, errors) = parse_source("switch (x) {\ncase 1:\ncase 2: let y = x; break;\ndefault:\ncase 3: y();\n}");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::Switch { cases, .. } => {
+            assert_eq!(cases.len(), 4);
+            assert!(cases[0].consequent.is_empty());
+            assert!(matches!(cases[1].consequent[..], [Statement::Declaration { .. }, Statement::Break { label: None, .. }]));
+            assert_eq!(cases[2].test, None);
+            assert!(matches!(cases[3].test, Some(Expression::Literal { .. })));
+        }
+        other => panic!("expected switch but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_switch() {
+    let (_, errors) = parse_source("switch (x) { default: break; default: }");
+    assert_eq!(errors[0].code, "E0107");
+    assert_eq!(errors[0].labels.len(), 1);
+
+    let (_, errors) = parse_source("switch (x) { x; }");
+    assert_eq!(errors[0].code, "E0101");
+    assert_eq!(errors[0].message, "expected 'case' or 'default'");
+}
+
+#[test]
+fn test_throw_and_try_statements() {
+    let (result, errors) = parse_source("throw Error('x');\ntry { a(); } catch (e) { e.message; }\ntry {} catch {} finally {}\ntry {} finally {}");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert!(matches!(result[0], Statement::Throw { .. }));
+    match &result[1] {
+        Statement::Try { handler: Some(handler), finalizer: None, .. } => {
+            assert_eq!(handler.param, Some("e".into()));
+            match handler.body.as_ref() {
+                Statement::Block { body, .. } => assert!(matches!(
+                    &body[0],
+                    Statement::ExpressionStatement { expression, .. }
+                        if matches!(expression.as_ref(), Expression::Member { property, .. } if property == "message")
+                )),
+                other => panic!("expected block but got {:?}", other),
+            }
+        }
+        other => panic!("expected try statement but got {:?}", other),
+    }
+    assert!(matches!(&result[2], Statement::Try { handler: Some(CatchClause { param: None, .. }), finalizer: Some(_), .. }));
+    assert!(matches!(result[3], Statement::Try { handler: None, finalizer: Some(_), .. }));
+}
+
+#[test]
+fn test_invalid_throw_and_try() {
+    let (_, errors) = parse_source("throw\nError('x');");
+    assert_eq!(errors[0].code, "E0102");
+    assert_eq!(errors[0].message, "expected an expression on the same line as throw");
+
+    let (_, errors) = parse_source("try {} x;");
+    assert_eq!(errors[0].code, "E0101");
+    assert_eq!(errors[0].message, "expected 'catch' or 'finally' after try block");
+}
+
+#[test]
+fn test_logical_precedence() {
+    // `&&` binds tighter than `||`, which binds looser than comparisons
+    let (result, errors) = parse_source("a || b && c == d;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Logical { operator: TokenType::PipePipe, right, .. } => match right.as_ref() {
+                Expression::Logical { operator: TokenType::AmpersandAmpersand, right, .. } => {
+                    assert!(matches!(right.as_ref(), Expression::Comparison { .. }))
+                }
+                other => panic!("expected && but got {:?}", other),
+            },
+            other => panic!("expected || but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+
+    let (result, errors) = parse_source("x ??= !a ?? b;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Assignment { operator: TokenType::QuestionQuestionAssign, value, .. } => assert!(matches!(
+                value.as_ref(),
+                Expression::Logical { operator: TokenType::QuestionQuestion, left, .. } if matches!(left.as_ref(), Expression::Unary { .. })
+            )),
+            other => panic!("expected assignment but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_nullish_coalescing_cannot_mix_with_logical_operators() {
+    for source in ["a ?? b || c;", "a || b ?? c;", "a ?? b && c;", "a && b ?? c;"] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors.len(), 1, "{}", source);
+        assert_eq!(errors[0].code, "E0108", "{}", source);
+    }
+
+    for source in ["(a ?? b) || c;", "a ?? (b && c);", "a ?? b ?? c;", "(a || b) ?? c;"] {
+        let (_, errors) = parse_source(source);
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+    }
+}
+
+#[test]
+fn test_binary_precedence() {
+    // each operator binds tighter than the one before it
+    let (result, errors) = parse_source("a | b ^ c & d == e < f << g + h * i ** j;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    let mut expression = match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => expression.as_ref(),
+        other => panic!("expected expression statement but got {:?}", other),
+    };
+    for expected in [
+        TokenType::Pipe,
+        TokenType::Caret,
+        TokenType::Ampersand,
+        TokenType::Equal,
+        TokenType::LessThan,
+        TokenType::LeftShift,
+        TokenType::Plus,
+        TokenType::Star,
+        TokenType::StarStar,
+    ] {
+        expression = match expression {
+            Expression::Binary { operator, right, .. } | Expression::Comparison { operator, right, .. } => {
+                assert_eq!(*operator, expected);
+                right.as_ref()
+            }
+            other => panic!("expected {:?} but got {:?}", expected, other),
+        };
+    }
+    assert!(matches!(expression, Expression::Identifier { .. }));
+}
+
+#[test]
+fn test_exponent_is_right_associative() {
+    let (result, errors) = parse_source("a ** b ** c;\na - b - c;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Binary { operator: TokenType::StarStar, left, right, .. } => {
+                assert!(matches!(left.as_ref(), Expression::Identifier { .. }));
+                assert!(matches!(right.as_ref(), Expression::Binary { operator: TokenType::StarStar, .. }));
+            }
+            other => panic!("expected ** but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+    match &result[1] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Binary { operator: TokenType::Minus, left, .. } => {
+                assert!(matches!(left.as_ref(), Expression::Binary { operator: TokenType::Minus, .. }))
+            }
+            other => panic!("expected - but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_unary_operand_of_exponent_needs_parentheses() {
+    for source in ["-2 ** 2;", "typeof a ** 2;", "2 ** -a ** 2;"] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors.len(), 1, "{}", source);
+        assert_eq!(errors[0].code, "E0111", "{}", source);
+    }
+
+    for source in ["(-2) ** 2;", "-(2 ** 2);", "2 ** -2;", "++a ** 2;", "a++ ** 2;"] {
+        let (_, errors) = parse_source(source);
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+    }
+}
+
+#[test]
+fn test_conditional_is_right_associative() {
+    let (result, errors) = parse_source("a ? b : c ? d : e;\nx = a ? b ? c : d : e;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Conditional { alternate, .. } => assert!(matches!(alternate.as_ref(), Expression::Conditional { .. })),
+            other => panic!("expected conditional but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+    match &result[1] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Assignment { value, .. } => match value.as_ref() {
+                Expression::Conditional { consequent, .. } => {
+                    assert!(matches!(consequent.as_ref(), Expression::Conditional { .. }))
+                }
+                other => panic!("expected conditional but got {:?}", other),
+            },
+            other => panic!("expected assignment but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+
+    let (_, errors) = parse_source("a ? b;");
+    assert_eq!(errors[0].message, "expected ':' in conditional expression");
+}
+
+#[test]
+fn test_sequence_and_arguments() {
+    let (result, errors) = parse_source("a = 1, b = 2;\nf(a, (b, c));");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => {
+            assert!(matches!(expression.as_ref(), Expression::Sequence { expressions, .. } if expressions.len() == 2))
+        }
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+    match &result[1] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Call { args, .. } => {
+                assert_eq!(args.len(), 2);
+                assert!(matches!(&args[1], Expression::Grouping { expression, .. } if matches!(expression.as_ref(), Expression::Sequence { .. })));
+            }
+            other => panic!("expected call but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_method_and_chained_calls() {
+    let (result, errors) = parse_source("n.toFixed(2);\nf(1)(2).x;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Call { callee, args, .. } => {
+                assert!(matches!(callee.as_ref(), Expression::Member { property, .. } if property == "toFixed"));
+                assert_eq!(args.len(), 1);
+            }
+            other => panic!("expected call but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+    match &result[1] {
+        Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+            Expression::Member { object, .. } => match object.as_ref() {
+                Expression::Call { callee, .. } => assert!(matches!(callee.as_ref(), Expression::Call { .. })),
+                other => panic!("expected call but got {:?}", other),
+            },
+            other => panic!("expected member but got {:?}", other),
+        },
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_grouping_parses_full_expressions() {
+    let (result, errors) = parse_source("(a == b);");
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &result[0] {
+        Statement::ExpressionStatement { expression, .. } => assert!(matches!(
+            expression.as_ref(),
+            Expression::Grouping { expression, .. } if matches!(expression.as_ref(), Expression::Comparison { .. })
+        )),
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_update_expressions() {
+    let (result, errors) = parse_source("++a;\nb--;\n-c++;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    let expressions: Vec<&Expression> = result
+        .iter()
+        .map(|statement| match statement {
+            Statement::ExpressionStatement { expression, .. } => expression.as_ref(),
+            other => panic!("expected expression statement but got {:?}", other),
+        })
+        .collect();
+    assert!(matches!(expressions[0], Expression::Update { operator: TokenType::PlusPlus, prefix: true, .. }));
+    assert!(matches!(expressions[1], Expression::Update { operator: TokenType::MinusMinus, prefix: false, .. }));
+    // postfix binds tighter than unary minus
+    assert!(matches!(
+        expressions[2],
+        Expression::Unary { right, .. } if matches!(right.as_ref(), Expression::Update { prefix: false, .. })
+    ));
+}
+
+#[test]
+fn test_postfix_update_is_restricted() {
+    // a line break before `++` makes it a prefix of the next statement
+    let (result, errors) = parse_source("a\n++b");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(result.len(), 2);
+    match &result[1] {
+        Statement::ExpressionStatement { expression, .. } => {
+            assert!(matches!(expression.as_ref(), Expression::Update { prefix: true, .. }))
+        }
+        other => panic!("expected expression statement but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_update_targets() {
+    for source in ["1++;", "++f();", "--(a + b);", "++a--;"] {
+        let (_, errors) = parse_source(source);
+        assert_eq!(errors[0].code, "E0105", "{}", source);
+    }
 }
@@ -1,5 +1,11 @@
 mod values;
+mod number;
+mod conversions;
 mod environment;
+mod errors;
+mod builtins;
 pub mod core;
+#[cfg(test)]
+mod tests;
 
-pub use core::Runtime;
+pub use core::{Runtime, STACK_SIZE};
@@ -0,0 +1,162 @@
+use super::conversions::{self, string_to_bigint, to_integer_or_infinity, to_numeric, to_string};
+use super::errors::{range_error, syntax_error, type_error};
+use std::rc::Rc;
+use super::number;
+use super::values::{ErrorObject, RuntimeValue};
+use crate::common::{BigInt, Diagnostic, Span};
+
+pub type Builtin = fn(&[RuntimeValue], Span) -> Result<RuntimeValue, Diagnostic>;
+
+/// Native functions, used when no user function with the same name is declared.
+pub fn get(name: &str) -> Option<Builtin> {
+    match name {
+        "BigInt" => Some(bigint),
+        "Number" => Some(number),
+        "parseInt" => Some(parse_int),
+        "parseFloat" => Some(parse_float),
+        "Error" => Some(|args, span| error("Error", args, span)),
+        "TypeError" => Some(|args, span| error("TypeError", args, span)),
+        "RangeError" => Some(|args, span| error("RangeError", args, span)),
+        "ReferenceError" => Some(|args, span| error("ReferenceError", args, span)),
+        "SyntaxError" => Some(|args, span| error("SyntaxError", args, span)),
+        _ => None,
+    }
+}
+
+/// Methods of primitives, called as `value.name(args)` with the value as `this`.
+pub type Method = fn(&RuntimeValue, &[RuntimeValue], Span) -> Result<RuntimeValue, Diagnostic>;
+
+pub fn method(value: &RuntimeValue, name: &str) -> Option<Method> {
+    match (value, name) {
+        (RuntimeValue::Number(_), "toString") => Some(number_to_string),
+        (RuntimeValue::Number(_), "toFixed") => Some(number_to_fixed),
+        (RuntimeValue::Number(_), "toExponential") => Some(number_to_exponential),
+        (RuntimeValue::Number(_), "toPrecision") => Some(number_to_precision),
+        _ => None,
+    }
+}
+
+fn bigint(args: &[RuntimeValue], span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let value = args.first().cloned().unwrap_or(RuntimeValue::Undefined);
+    let result = match &value {
+        RuntimeValue::BigInt(n) => n.clone(),
+        RuntimeValue::Number(n) => BigInt::from_f64(*n).ok_or_else(|| {
+            range_error(format!("The number {} cannot be converted to a BigInt because it is not an integer", value), span)
+        })?,
+        RuntimeValue::Boolean(b) => BigInt::from_u64(*b as u64),
+        RuntimeValue::String(s) => string_to_bigint(s)
+            .ok_or_else(|| syntax_error(format!("Cannot convert {} to a BigInt", s), span))?,
+        // objects are converted through their string
+        RuntimeValue::Error(_) => string_to_bigint(&to_string(&value))
+            .ok_or_else(|| syntax_error(format!("Cannot convert {} to a BigInt", value), span))?,
+        RuntimeValue::Null | RuntimeValue::Undefined => {
+            return Err(type_error(format!("Cannot convert {} to a BigInt", value), span))
+        }
+    };
+
+    Ok(RuntimeValue::BigInt(result))
+}
+
+/// `Number(value)`, which unlike the operators converts a BigInt too.
+fn number(args: &[RuntimeValue], _span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let result = match args.first().map(to_numeric) {
+        None => 0.0,
+        Some(RuntimeValue::BigInt(n)) => n.to_f64(),
+        Some(RuntimeValue::Number(n)) => n,
+        Some(_) => f64::NAN,
+    };
+
+    Ok(RuntimeValue::Number(result))
+}
+
+fn parse_int(args: &[RuntimeValue], span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let string = to_string(argument(args, 0));
+    let radix = conversions::to_int32(conversions::to_number(argument(args, 1), span)?);
+    Ok(RuntimeValue::Number(conversions::parse_int(&string, radix)))
+}
+
+fn parse_float(args: &[RuntimeValue], _span: Span) -> Result<RuntimeValue, Diagnostic> {
+    Ok(RuntimeValue::Number(conversions::parse_float(&to_string(argument(args, 0)))))
+}
+
+/// `Error(message)` and the other error constructors, which don't need `new`.
+fn error(name: &str, args: &[RuntimeValue], span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let message = match args.first() {
+        None | Some(RuntimeValue::Undefined) => String::new(),
+        Some(message) => to_string(message),
+    };
+
+    Ok(RuntimeValue::Error(Rc::new(ErrorObject::new(name, message, span))))
+}
+
+fn number_to_string(this: &RuntimeValue, args: &[RuntimeValue], span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let value = this_number_value(this, "toString", span)?;
+    let radix = match args.first() {
+        None | Some(RuntimeValue::Undefined) => 10.0,
+        Some(_) => to_integer_or_infinity(argument(args, 0), span)?,
+    };
+    if !(2.0..=36.0).contains(&radix) {
+        return Err(range_error("toString() radix must be between 2 and 36", span));
+    }
+
+    Ok(RuntimeValue::String(number::to_string_radix(value, radix as u32)))
+}
+
+fn number_to_fixed(this: &RuntimeValue, args: &[RuntimeValue], span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let value = this_number_value(this, "toFixed", span)?;
+    let digits = to_integer_or_infinity(argument(args, 0), span)?;
+    if !(0.0..=100.0).contains(&digits) {
+        return Err(range_error("toFixed() digits argument must be between 0 and 100", span));
+    }
+    if !value.is_finite() || value.abs() >= 1e21 {
+        return Ok(RuntimeValue::String(number::to_string(value)));
+    }
+
+    Ok(RuntimeValue::String(number::to_fixed(value, digits as usize)))
+}
+
+fn number_to_exponential(this: &RuntimeValue, args: &[RuntimeValue], span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let value = this_number_value(this, "toExponential", span)?;
+    let digits = to_integer_or_infinity(argument(args, 0), span)?;
+    if !value.is_finite() {
+        return Ok(RuntimeValue::String(number::to_string(value)));
+    }
+    if !(0.0..=100.0).contains(&digits) {
+        return Err(range_error("toExponential() argument must be between 0 and 100", span));
+    }
+
+    let digits = match args.first() {
+        None | Some(RuntimeValue::Undefined) => None,
+        Some(_) => Some(digits as usize),
+    };
+    Ok(RuntimeValue::String(number::to_exponential(value, digits)))
+}
+
+fn number_to_precision(this: &RuntimeValue, args: &[RuntimeValue], span: Span) -> Result<RuntimeValue, Diagnostic> {
+    let value = this_number_value(this, "toPrecision", span)?;
+    if matches!(args.first(), None | Some(RuntimeValue::Undefined)) {
+        return Ok(RuntimeValue::String(number::to_string(value)));
+    }
+    let precision = to_integer_or_infinity(argument(args, 0), span)?;
+    if !value.is_finite() {
+        return Ok(RuntimeValue::String(number::to_string(value)));
+    }
+    if !(1.0..=100.0).contains(&precision) {
+        return Err(range_error("toPrecision() argument must be between 1 and 100", span));
+    }
+
+    Ok(RuntimeValue::String(number::to_precision(value, precision as usize)))
+}
+
+/// thisNumberValue, the number a Number method is called on.
+fn this_number_value(this: &RuntimeValue, method: &str, span: Span) -> Result<f64, Diagnostic> {
+    match this {
+        RuntimeValue::Number(n) => Ok(*n),
+        _ => Err(type_error(format!("Number.prototype.{} requires that 'this' be a Number", method), span)),
+    }
+}
+
+/// The argument at `index`, `undefined` when it's missing.
+fn argument(args: &[RuntimeValue], index: usize) -> &RuntimeValue {
+    args.get(index).unwrap_or(&RuntimeValue::Undefined)
+}
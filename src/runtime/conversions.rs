@@ -0,0 +1,214 @@
+//! The abstract conversion operations, shared by the operators and built-ins.
+
+use super::errors::type_error;
+use super::number;
+use super::values::RuntimeValue;
+use crate::common::{BigInt, Diagnostic, Span};
+use crate::tokenizer::{is_line_terminator, is_whitespace};
+
+/// ToPrimitive, objects become their string since an error's `valueOf` is
+/// the error itself.
+pub fn to_primitive(value: &RuntimeValue) -> RuntimeValue {
+    match value {
+        RuntimeValue::Error(_) => RuntimeValue::String(to_string(value)),
+        primitive => primitive.clone(),
+    }
+}
+
+/// ToBoolean, whether the value is truthy.
+pub fn to_boolean(value: &RuntimeValue) -> bool {
+    match value {
+        RuntimeValue::Number(n) => *n != 0.0 && !n.is_nan(),
+        RuntimeValue::BigInt(n) => !n.is_zero(),
+        RuntimeValue::String(value) => !value.is_empty(),
+        RuntimeValue::Boolean(value) => *value,
+        RuntimeValue::Null | RuntimeValue::Undefined => false,
+        RuntimeValue::Error(_) => true,
+    }
+}
+
+/// ToNumeric, a BigInt stays one and anything else becomes a number.
+pub fn to_numeric(value: &RuntimeValue) -> RuntimeValue {
+    match value {
+        RuntimeValue::Number(n) => RuntimeValue::Number(*n),
+        RuntimeValue::BigInt(n) => RuntimeValue::BigInt(n.clone()),
+        RuntimeValue::String(value) => RuntimeValue::Number(string_to_number(value)),
+        RuntimeValue::Boolean(value) => RuntimeValue::Number(*value as u8 as f64),
+        RuntimeValue::Null => RuntimeValue::Number(0.0),
+        RuntimeValue::Undefined => RuntimeValue::Number(f64::NAN),
+        RuntimeValue::Error(_) => to_numeric(&to_primitive(value)),
+    }
+}
+
+/// ToNumber, which throws for a BigInt instead of losing precision silently.
+pub fn to_number(value: &RuntimeValue, span: Span) -> Result<f64, Diagnostic> {
+    match to_numeric(value) {
+        RuntimeValue::Number(n) => Ok(n),
+        _ => Err(type_error("Cannot convert a BigInt value to a number", span)),
+    }
+}
+
+/// ToIntegerOrInfinity, the number without its fraction and `NaN` as 0.
+pub fn to_integer_or_infinity(value: &RuntimeValue, span: Span) -> Result<f64, Diagnostic> {
+    let number = to_number(value, span)?;
+    // `+ 0.0` turns -0 into 0
+    Ok(if number.is_nan() { 0.0 } else { number.trunc() + 0.0 })
+}
+
+/// ToUint32 of a value that is already a number.
+pub fn to_uint32(value: f64) -> u32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32
+}
+
+/// ToInt32 of a value that is already a number.
+pub fn to_int32(value: f64) -> i32 {
+    to_uint32(value) as i32
+}
+
+/// ToString, which `Display` of a value uses too.
+pub fn to_string(value: &RuntimeValue) -> String {
+    match value {
+        RuntimeValue::Number(n) => number::to_string(*n),
+        RuntimeValue::BigInt(n) => n.to_string_radix(10),
+        RuntimeValue::String(value) => value.clone(),
+        RuntimeValue::Boolean(value) => value.to_string(),
+        RuntimeValue::Null => String::from("null"),
+        RuntimeValue::Undefined => String::from("undefined"),
+        RuntimeValue::Error(error) => error.to_string(),
+    }
+}
+
+/// StringToNumber: surrounding whitespace is ignored, an empty string is 0 and
+/// anything that isn't a StrNumericLiteral is `NaN`.
+pub fn string_to_number(string: &str) -> f64 {
+    let string = string.trim_matches(is_str_whitespace);
+    if string.is_empty() {
+        return 0.0;
+    }
+
+    if let Some((radix, digits)) = non_decimal_digits(string) {
+        return BigInt::parse(digits, radix).map_or(f64::NAN, |n| n.to_f64());
+    }
+    if decimal_prefix_length(string) != string.len() {
+        return f64::NAN;
+    }
+    parse_decimal(string)
+}
+
+/// StringToBigInt: surrounding whitespace is ignored, an empty string is 0n, and
+/// only decimal digits may have a sign. Separators and fractions are not allowed.
+pub fn string_to_bigint(string: &str) -> Option<BigInt> {
+    let string = string.trim_matches(is_str_whitespace);
+    if string.is_empty() {
+        return Some(BigInt::zero());
+    }
+
+    if let Some((radix, digits)) = non_decimal_digits(string) {
+        return BigInt::parse(digits, radix);
+    }
+    match string.strip_prefix('-') {
+        Some(digits) => BigInt::parse(digits, 10).map(|n| n.neg()),
+        None => BigInt::parse(string.strip_prefix('+').unwrap_or(string), 10),
+    }
+}
+
+/// `parseInt(string, radix)`, the integer at the start of the string. A radix
+/// of 0 is 10, or 16 for a `0x` prefix.
+pub fn parse_int(string: &str, radix: i32) -> f64 {
+    let string = string.trim_start_matches(is_str_whitespace);
+    let (sign, string) = match string.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, string.strip_prefix('+').unwrap_or(string)),
+    };
+
+    let (radix, string) = match radix {
+        0 | 16 => match string.get(..2) {
+            Some("0x" | "0X") => (16, &string[2..]),
+            _ => (if radix == 0 { 10 } else { 16 }, string),
+        },
+        2..=36 => (radix as u32, string),
+        _ => return f64::NAN,
+    };
+
+    let end = string.find(|c: char| !c.is_digit(radix)).unwrap_or(string.len());
+    match BigInt::parse(&string[..end], radix) {
+        // a zero keeps its sign, `parseInt('-0')` is -0
+        Some(n) => sign * n.to_f64(),
+        None => f64::NAN,
+    }
+}
+
+/// `parseFloat(string)`, the longest decimal literal at the start of the string.
+pub fn parse_float(string: &str) -> f64 {
+    let string = string.trim_start_matches(is_str_whitespace);
+    match decimal_prefix_length(string) {
+        0 => f64::NAN,
+        length => parse_decimal(&string[..length]),
+    }
+}
+
+/// The radix and digits of a `0x`, `0o` or `0b` literal, which can't have a sign.
+fn non_decimal_digits(string: &str) -> Option<(u32, &str)> {
+    let radix = match string.get(..2)? {
+        "0x" | "0X" => 16,
+        "0o" | "0O" => 8,
+        "0b" | "0B" => 2,
+        _ => return None,
+    };
+    Some((radix, &string[2..]))
+}
+
+/// The length of the longest StrDecimalLiteral at the start of `string`, 0 if
+/// there is none: a sign, then `Infinity` or digits with an optional fraction
+/// and exponent. Unlike in source code, there are no `_` separators.
+fn decimal_prefix_length(string: &str) -> usize {
+    let bytes = string.as_bytes();
+    let digits_from = |start: usize| start + bytes[start..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let start = if matches!(bytes.first(), Some(b'+' | b'-')) { 1 } else { 0 };
+    if string[start..].starts_with("Infinity") {
+        return start + "Infinity".len();
+    }
+
+    let mut end = digits_from(start);
+    let mut has_digits = end > start;
+    if bytes.get(end) == Some(&b'.') {
+        let fraction_end = digits_from(end + 1);
+        has_digits |= fraction_end > end + 1;
+        end = fraction_end;
+    }
+    if !has_digits {
+        return 0;
+    }
+
+    // an exponent only counts when it has digits, `1e` is 1 followed by `e`
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let exponent_start = if matches!(bytes.get(end + 1), Some(b'+' | b'-')) { end + 2 } else { end + 1 };
+        let exponent_end = digits_from(exponent_start);
+        if exponent_end > exponent_start {
+            end = exponent_end;
+        }
+    }
+    end
+}
+
+/// The value of a whole StrDecimalLiteral.
+fn parse_decimal(literal: &str) -> f64 {
+    let (sign, unsigned) = match literal.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, literal.strip_prefix('+').unwrap_or(literal)),
+    };
+    if unsigned == "Infinity" {
+        return sign * f64::INFINITY;
+    }
+    sign * unsigned.parse::<f64>().unwrap_or(f64::NAN)
+}
+
+/// StrWhiteSpaceChar, the same WhiteSpace and LineTerminator as in source code.
+/// Unlike `char::is_whitespace` it has U+FEFF and not U+0085.
+fn is_str_whitespace(c: char) -> bool {
+    is_whitespace(c) || is_line_terminator(c)
+}
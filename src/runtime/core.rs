@@ -1,157 +1,646 @@
+use super::builtins;
+use super::conversions::{to_boolean, to_int32, to_number, to_numeric, to_primitive, to_uint32};
 use super::environment::Environment;
+use super::errors::{internal_error, range_error, reference_error, type_error, Exception};
 use super::values::RuntimeValue;
 use crate::common::{*, ast::*};
 
+/// How many calls can be nested before a RangeError.
+const MAX_CALL_DEPTH: usize = 2000;
+
+/// The stack the thread running a `Runtime` needs to reach `MAX_CALL_DEPTH`
+/// without overflowing, even in an unoptimized build.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
 pub struct Runtime {
-    environment: Environment,
+    pub(super) environment: Environment,
+    // the number of calls this runtime runs inside of
+    call_depth: usize,
+}
+
+/// How a statement finished, anything but `Normal` skips the statements after it.
+enum Completion {
+    Normal,
+    Return(RuntimeValue),
+    // with the label to jump to, if any
+    Break(Option<String>),
+    Continue(Option<String>),
 }
 
 impl Runtime {
     pub fn new() -> Self {
-        Self {
-            environment: Environment::new(),
+        let mut environment = Environment::new();
+        // the value properties of the global object, which can't be changed
+        for (name, value) in [
+            ("NaN", RuntimeValue::Number(f64::NAN)),
+            ("Infinity", RuntimeValue::Number(f64::INFINITY)),
+            ("undefined", RuntimeValue::Undefined),
+        ] {
+            environment.declare(name.into(), value, true);
+        }
+
+        Self { environment, call_depth: 0 }
+    }
+
+    /// Runs the program until its end or until an exception isn't caught,
+    /// which is returned as the diagnostic to report.
+    pub fn interpret(&mut self, statements: Vec<Statement>) -> Result<(), Diagnostic> {
+        for statement in statements.iter() {
+            self.evaluate_statement(statement).map_err(|exception| exception.diagnostic)?;
         }
+
+        Ok(())
     }
 
-    pub fn interpret(&mut self, statements: Vec<Statement>) {
+    fn evaluate_statements(&mut self, statements: &[Statement]) -> Result<Completion, Exception> {
         for statement in statements {
-            if let Err(error_message) = self.evaluate_statement(statement) {
-                println!("runtime>: {}", error_message);
+            match self.evaluate_statement(statement)? {
+                Completion::Normal => (),
+                completion => return Ok(completion),
             }
         }
+
+        Ok(Completion::Normal)
     }
 
-    fn evaluate_statement(&mut self, statement: Statement) -> Result<(), String> {
+    fn evaluate_statement(&mut self, statement: &Statement) -> Result<Completion, Exception> {
+      let span = statement.span();
       match statement {
-        Statement::Declaration { is_const, name, value} => {
-          let value: RuntimeValue = match &*value {
+        Statement::Declaration { is_const, name, value, .. } => {
+          let value: RuntimeValue = match value.as_ref() {
             Some(expr) => self.evalutate_expression(expr)?,
             _ => RuntimeValue::Undefined,
           };
 
-          if self.environment.variables.contains_key(&name) {
-            return Err(format!("variable {} already declared", name));
-          }
-
-          if is_const {
-            self.environment.constants.insert(name.clone());
-          }
-
           println!("runtime>: created {:?} = {:?}", name, value);
-          self.environment.variables.insert(name, value);
+          self.declare(name, value, *is_const, span)?;
         },
-        Statement::ExpressionStatement { expression} => {
-          let value = self.evalutate_expression(&expression)?;
+        Statement::ExpressionStatement { expression, .. } => {
+          let value = self.evalutate_expression(expression)?;
 
           println!("runtime>: {:?}", value);
         },
-        Statement::FunctionDeclaration { name, params, body } => {
+        Statement::FunctionDeclaration { name, params, .. } => {
           println!("runtime>: created {:?}({:?})", name, params);
 
-          self.environment.functions.insert(name.clone(), Statement::FunctionDeclaration { name, params, body });
+          self.environment.declare_function(name.clone(), statement.clone());
+        },
+        Statement::Return { expression, .. } => {
+          let value = self.evalutate_expression(expression)?;
+          return Ok(Completion::Return(value));
+        },
+        Statement::If { test, consequent, alternate, .. } => {
+          if to_boolean(&self.evalutate_expression(test)?) {
+            return self.evaluate_statement(consequent);
+          }
+          if let Some(alternate) = alternate {
+            return self.evaluate_statement(alternate);
+          }
+        },
+        Statement::Block { body, .. } => {
+          self.environment.enter_block();
+          // the block is left even when a statement in it fails
+          let completion = self.evaluate_statements(body);
+          self.environment.exit_block();
+          return completion;
+        },
+        Statement::While { .. }
+        | Statement::DoWhile { .. }
+        | Statement::For { .. }
+        | Statement::ForIn { .. }
+        | Statement::ForOf { .. } => {
+          return self.evaluate_loop(statement, &[]);
+        },
+        Statement::Switch { discriminant, cases, .. } => {
+          let value = self.evalutate_expression(discriminant)?;
+          // all the cases share one scope
+          self.environment.enter_block();
+          let completion = self.evaluate_switch_cases(&value, cases);
+          self.environment.exit_block();
+          return match completion? {
+            Completion::Break(None) => Ok(Completion::Normal),
+            completion => Ok(completion),
+          };
+        },
+        Statement::Throw { expression, .. } => {
+          let value = self.evalutate_expression(expression)?;
+          return Err(Exception::thrown(value, span));
+        },
+        Statement::Try { block, handler, finalizer, .. } => {
+          let result = match (self.evaluate_statement(block), handler) {
+            (Err(Exception { value: Some(value), .. }), Some(handler)) => self.evaluate_catch(handler, value),
+            (result, _) => result,
+          };
+
+          if let Some(finalizer) = finalizer {
+            // the finally block runs in any case, and overrides how the rest completed if it jumps or throws
+            match self.evaluate_statement(finalizer)? {
+              Completion::Normal => (),
+              completion => return Ok(completion),
+            }
+          }
+          return result;
         },
-        Statement::Return { .. } => return Err("return statements can't be used outside of functions".into()),
+        Statement::Labeled { .. } => return self.evaluate_labeled(statement),
+        Statement::Break { label, .. } => return Ok(Completion::Break(label.clone())),
+        Statement::Continue { label, .. } => return Ok(Completion::Continue(label.clone())),
       }
 
-      Ok(())
+      Ok(Completion::Normal)
     }
 
-    fn evalutate_expression(&self, expression: &Expression) -> Result<RuntimeValue, String> {
-        match expression {
-            Expression::Literal(literal) => Ok(RuntimeValue::from(literal.clone())),
-            Expression::Identifier(value) => match self.environment.variables.get(value) {
-                Some(value) => Ok(value.clone()),
-                None => Ok(RuntimeValue::Undefined),
+    fn evaluate_catch(&mut self, handler: &CatchClause, value: RuntimeValue) -> Result<Completion, Exception> {
+        self.environment.enter_block();
+        if let Some(param) = &handler.param {
+            self.environment.declare(param.clone(), value, false);
+        }
+        let completion = self.evaluate_statement(&handler.body);
+        self.environment.exit_block();
+        completion
+    }
+
+    fn evaluate_labeled(&mut self, statement: &Statement) -> Result<Completion, Exception> {
+        // in `a: b: while (...)` both labels belong to the loop
+        let mut labels = vec![];
+        let mut body = statement;
+        while let Statement::Labeled { label, body: labeled, .. } = body {
+            labels.push(label.clone());
+            body = labeled;
+        }
+
+        let completion = match body {
+            Statement::While { .. }
+            | Statement::DoWhile { .. }
+            | Statement::For { .. }
+            | Statement::ForIn { .. }
+            | Statement::ForOf { .. } => self.evaluate_loop(body, &labels)?,
+            _ => self.evaluate_statement(body)?,
+        };
+
+        match completion {
+            Completion::Break(Some(label)) if labels.contains(&label) => Ok(Completion::Normal),
+            completion => Ok(completion),
+        }
+    }
+
+    /// Runs a loop statement, `labels` are the ones `continue` can use to jump to its next iteration.
+    fn evaluate_loop(&mut self, statement: &Statement, labels: &[String]) -> Result<Completion, Exception> {
+        match statement {
+            Statement::While { test, body, .. } => {
+                while to_boolean(&self.evalutate_expression(test)?) {
+                    let completion = self.evaluate_statement(body)?;
+                    if let Some(completion) = loop_exit(completion, labels) {
+                        return Ok(completion);
+                    }
+                }
+            }
+            Statement::DoWhile { body, test, .. } => loop {
+                let completion = self.evaluate_statement(body)?;
+                if let Some(completion) = loop_exit(completion, labels) {
+                    return Ok(completion);
+                }
+                if !to_boolean(&self.evalutate_expression(test)?) {
+                    break;
+                }
             },
-            Expression::Grouping { expression } => self.evalutate_expression(&**expression),
-            Expression::Unary { operator, right } => {
-                let right_value = self.evalutate_expression(right.as_ref())?;
-                match (operator, right_value) {
-                    (TokenType::Minus, RuntimeValue::Number(n)) => Ok(RuntimeValue::Number(-n)),
-                    _ => Err(format!("invalid unary operator: {:?}", operator)),
+            Statement::For { init, test, update, body, .. } => {
+                // every iteration gets its own copy of the `let` declared in the head
+                let per_iteration = match init.as_deref() {
+                    Some(Statement::Declaration { is_const: false, name, .. }) => vec![name.clone()],
+                    _ => vec![],
+                };
+
+                self.environment.enter_block();
+                let result = match init {
+                    Some(init) => self.evaluate_statement(init),
+                    None => Ok(Completion::Normal),
+                };
+                self.environment.enter_block();
+                let result = result.and_then(|_| {
+                    self.evaluate_for_iterations(&per_iteration, test.as_deref(), update.as_deref(), body, labels)
+                });
+                self.environment.exit_block();
+                self.environment.exit_block();
+                return result;
+            }
+            Statement::ForIn { left, right, body, .. } | Statement::ForOf { left, right, body, .. } => {
+                let iterated = self.evalutate_expression(right)?;
+                let values = match statement {
+                    Statement::ForOf { .. } => for_of_values(iterated, right.span())?,
+                    _ => for_in_keys(&iterated),
+                };
+
+                for value in values {
+                    // a new scope for every value, like the iterations of a `for`
+                    self.environment.enter_block();
+                    let completion = self.bind_for_value(left, value).map_err(Exception::from).and_then(|_| self.evaluate_statement(body));
+                    self.environment.exit_block();
+                    if let Some(completion) = loop_exit(completion?, labels) {
+                        return Ok(completion);
+                    }
+                }
+            }
+            _ => return Err(internal_error("expected a loop statement", statement.span()).into()),
+        }
+
+        Ok(Completion::Normal)
+    }
+
+    fn evaluate_for_iterations(
+        &mut self,
+        per_iteration: &[String],
+        test: Option<&Expression>,
+        update: Option<&Expression>,
+        body: &Statement,
+        labels: &[String],
+    ) -> Result<Completion, Exception> {
+        self.environment.renew_block(per_iteration);
+        loop {
+            if let Some(test) = test {
+                if !to_boolean(&self.evalutate_expression(test)?) {
+                    return Ok(Completion::Normal);
+                }
+            }
+
+            let completion = self.evaluate_statement(body)?;
+            if let Some(completion) = loop_exit(completion, labels) {
+                return Ok(completion);
+            }
+
+            self.environment.renew_block(per_iteration);
+            if let Some(update) = update {
+                self.evalutate_expression(update)?;
+            }
+        }
+    }
+
+    /// Runs the cases from the first one matching `value`, or from `default`,
+    /// falling through the following ones until something completes abruptly.
+    fn evaluate_switch_cases(&mut self, value: &RuntimeValue, cases: &[SwitchCase]) -> Result<Completion, Exception> {
+        let mut matched = None;
+        for (index, case) in cases.iter().enumerate() {
+            if let Some(test) = &case.test {
+                if self.evalutate_expression(test)?.is_strictly_equal(value) {
+                    matched = Some(index);
+                    break;
+                }
+            }
+        }
+
+        let Some(first) = matched.or_else(|| cases.iter().position(|case| case.test.is_none())) else {
+            return Ok(Completion::Normal);
+        };
+        for case in &cases[first..] {
+            let completion = self.evaluate_statements(&case.consequent)?;
+            if !matches!(completion, Completion::Normal) {
+                return Ok(completion);
+            }
+        }
+
+        Ok(Completion::Normal)
+    }
+
+    fn bind_for_value(&mut self, left: &Statement, value: RuntimeValue) -> Result<(), Diagnostic> {
+        match left {
+            Statement::Declaration { is_const, name, span, .. } => self.declare(name, value, *is_const, *span)?,
+            Statement::ExpressionStatement { expression, .. } => match expression.as_ref() {
+                Expression::Identifier { name, span } => {
+                    self.assign_variable(name, value, *span)?;
                 }
+                _ => return Err(internal_error("invalid assignment target", expression.span())),
+            },
+            _ => return Err(internal_error("expected a declaration or a variable", left.span())),
+        }
+        Ok(())
+    }
+
+    fn evalutate_expression(&mut self, expression: &Expression) -> Result<RuntimeValue, Exception> {
+        match expression {
+            Expression::Literal { value, .. } => Ok(RuntimeValue::from(value.clone())),
+            Expression::Identifier { name, span } => Ok(self.get_variable(name, *span)?),
+            Expression::Grouping { expression, .. } => self.evalutate_expression(expression),
+            Expression::Unary { operator, right, span } => self.unary_operation(operator, right, *span),
+            Expression::Update { operator, prefix, target, span } => {
+                let name = match target.as_ref() {
+                    Expression::Identifier { name, .. } => name,
+                    _ => return Err(internal_error("invalid assignment target", target.span()).into()),
+                };
+
+                let increment = *operator == TokenType::PlusPlus;
+                let (old_value, new_value) = match self.get_variable(name, target.span())? {
+                    RuntimeValue::BigInt(n) => {
+                        let one = BigInt::from_u64(1);
+                        let new_value = if increment { n.add(&one) } else { n.sub(&one) };
+                        (RuntimeValue::BigInt(n), RuntimeValue::BigInt(new_value))
+                    }
+                    value => {
+                        let n = to_number(&value, target.span())?;
+                        (RuntimeValue::Number(n), RuntimeValue::Number(if increment { n + 1.0 } else { n - 1.0 }))
+                    }
+                };
+
+                self.assign_variable(name, new_value.clone(), *span)?;
+                Ok(if *prefix { new_value } else { old_value })
             }
             Expression::Binary {
                 left,
                 operator,
                 right,
+                span,
             } => {
                 let left_value = self.evalutate_expression(left.as_ref())?;
                 let right_value = self.evalutate_expression(right.as_ref())?;
 
-                match (left_value.clone(), operator, right_value.clone()) {
-                    (RuntimeValue::Number(a), TokenType::Star, RuntimeValue::Number(b)) => {
-                        Ok(RuntimeValue::Number(a * b))
-                    }
-                    (RuntimeValue::Number(a), TokenType::Slash, RuntimeValue::Number(b)) => {
-                        Ok(RuntimeValue::Number(a / b))
-                    }
-                    (RuntimeValue::Number(a), TokenType::Plus, RuntimeValue::Number(b)) => {
-                        Ok(RuntimeValue::Number(a + b))
-                    }
-                    (RuntimeValue::Number(a), TokenType::Minus, RuntimeValue::Number(b)) => {
-                        Ok(RuntimeValue::Number(a - b))
+                Ok(self.binary_operation(left_value, left.span(), operator, right_value, right.span(), *span)?)
+            }
+            Expression::Logical { left, operator, right, .. } => {
+                let left_value = self.evalutate_expression(left)?;
+                if short_circuits(operator, &left_value) {
+                    return Ok(left_value);
+                }
+                self.evalutate_expression(right)
+            }
+            Expression::Conditional { test, consequent, alternate, .. } => {
+                if to_boolean(&self.evalutate_expression(test)?) {
+                    self.evalutate_expression(consequent)
+                } else {
+                    self.evalutate_expression(alternate)
+                }
+            }
+            Expression::Sequence { expressions, .. } => {
+                let mut value = RuntimeValue::Undefined;
+                for expression in expressions {
+                    value = self.evalutate_expression(expression)?;
+                }
+                Ok(value)
+            }
+            Expression::Assignment { target, operator, value, span } => {
+                let name = match target.as_ref() {
+                    Expression::Identifier { name, .. } => name,
+                    _ => return Err(internal_error("invalid assignment target", target.span()).into()),
+                };
+
+                // `a ||= b` doesn't assign at all when `a` is truthy
+                if let Some(logical_operator) = operator.logical_assignment_operator() {
+                    let current = self.get_variable(name, target.span())?;
+                    if short_circuits(&logical_operator, &current) {
+                        return Ok(current);
                     }
-                    _ => Err(format!(
-                        "unhandled binary expression: {:?} {:?} {:?}",
-                        left_value, operator, right_value
-                    )),
                 }
+
+                let value = match operator.compound_assignment_operator() {
+                    Some(binary_operator) => {
+                        let current = self.get_variable(name, target.span())?;
+                        let right_value = self.evalutate_expression(value)?;
+                        self.binary_operation(current, target.span(), &binary_operator, right_value, value.span(), *span)?
+                    }
+                    None => self.evalutate_expression(value)?,
+                };
+
+                Ok(self.assign_variable(name, value, *span)?)
             }
             Expression::Comparison {
                 left,
                 operator,
                 right,
+                span,
             } => {
                 let left_value = self.evalutate_expression(left.as_ref())?;
                 let right_value = self.evalutate_expression(right.as_ref())?;
 
-                // TODO: comparing every type vs every time is not efficient and too much boilerplate
-                match (left_value.clone(), right_value.clone()) {
-                    (RuntimeValue::Number(a), RuntimeValue::Number(b)) => {
-                        self.compare_numbers(a, b, operator)
-                    }
-                    (RuntimeValue::Boolean(a), RuntimeValue::Boolean(b)) => {
-                        self.compare_booleans(a, b, operator)
-                    }
-                    (RuntimeValue::String(a), RuntimeValue::String(b)) => {
-                        self.compare_strings(&a, &b, operator)
-                    }
-                    (RuntimeValue::Null, RuntimeValue::Null) => self.compare_nulls(operator),
-                    (RuntimeValue::Undefined, RuntimeValue::Undefined) => {
-                        self.compare_undefinds(operator)
+                let result = match operator {
+                    TokenType::Equal => left_value.is_loosely_equal(&right_value),
+                    TokenType::NotEqual => !left_value.is_loosely_equal(&right_value),
+                    TokenType::StrictEqual => left_value.is_strictly_equal(&right_value),
+                    TokenType::StrictNotEqual => !left_value.is_strictly_equal(&right_value),
+                    // `a > b` is `b < a`, and `a <= b` is `!(b < a)` unless they can't be ordered
+                    TokenType::LessThan => left_value.is_less_than(&right_value) == Some(true),
+                    TokenType::GreaterThan => right_value.is_less_than(&left_value) == Some(true),
+                    TokenType::LessThanOrEqual => right_value.is_less_than(&left_value) == Some(false),
+                    TokenType::GreaterThanOrEqual => left_value.is_less_than(&right_value) == Some(false),
+                    _ => {
+                        return Err(internal_error(format!("invalid comparison operator: {:?}", operator), *span).into())
                     }
-                    (RuntimeValue::Null, RuntimeValue::Undefined) => {
-                        self.compare_null_undefined(operator)
-                    }
-                    (RuntimeValue::Undefined, RuntimeValue::Null) => {
-                        self.compare_null_undefined(operator)
-                    }
-                    _ => Err(format!(
-                        "unhandled comparison expression: {:?} {:?} {:?}",
-                        left_value, operator, right_value
-                    )),
+                };
+                Ok(RuntimeValue::Boolean(result))
+            },
+            Expression::Member { object, property, span } => match self.evalutate_expression(object)? {
+                RuntimeValue::Error(error) => Ok(error.get(property)),
+                value @ (RuntimeValue::Null | RuntimeValue::Undefined) => {
+                    Err(type_error(format!("Cannot read properties of {} (reading '{}')", value, property), *span).into())
                 }
+                // primitives don't have properties yet
+                _ => Ok(RuntimeValue::Undefined),
             },
-            Expression::Call { callee, args } => self.call_function(callee, &*args),
+            Expression::Call { callee, args, span } => match callee.as_ref() {
+                Expression::Identifier { name, .. } => self.call_function(name, args, *span),
+                Expression::Member { object, property, .. } => self.call_method(object, property, args, *span),
+                // there are no function values, only declared and built-in functions
+                callee => {
+                    let value = self.evalutate_expression(callee)?;
+                    Err(type_error(format!("{} is not a function", value), callee.span()).into())
+                }
+            },
+            Expression::Template { quasis, expressions, .. } => {
+                let mut string = quasis[0].clone();
+                for (expression, quasi) in expressions.iter().zip(quasis.iter().skip(1)) {
+                    string += &self.evalutate_expression(expression)?.to_string();
+                    string += quasi;
+                }
+
+                Ok(RuntimeValue::String(string))
+            }
             // Expression::Return { expression } => self.evalutate_expression(expression),
         }
     }
 
-    fn call_function(&self, callee: &String, args: &Vec<Expression>) -> Result<RuntimeValue, String> {
-      let function = self.get_function(callee)?;
+    fn unary_operation(&mut self, operator: &TokenType, right: &Expression, span: Span) -> Result<RuntimeValue, Exception> {
+        match (operator, right) {
+            // an undeclared variable is "undefined" instead of a ReferenceError
+            (TokenType::Typeof, Expression::Identifier { name, .. }) if self.environment.get(name).is_none() => {
+                let is_function = self.environment.get_function(name).is_some() || builtins::get(name).is_some();
+                return Ok(RuntimeValue::String(if is_function { "function" } else { "undefined" }.into()));
+            }
+            // `typeof (undeclared)` too
+            (TokenType::Typeof, Expression::Grouping { expression, .. }) => return self.unary_operation(operator, expression, span),
+            (TokenType::Delete, _) => return self.delete(right),
+            _ => (),
+        }
+
+        let value = self.evalutate_expression(right)?;
+        let result = match (operator, &value) {
+            (TokenType::Typeof, value) => RuntimeValue::String(value.type_of().into()),
+            (TokenType::Void, _) => RuntimeValue::Undefined,
+            (TokenType::Bang, value) => RuntimeValue::Boolean(!to_boolean(value)),
+            (TokenType::Minus, RuntimeValue::BigInt(n)) => RuntimeValue::BigInt(n.neg()),
+            (TokenType::Tilde, RuntimeValue::BigInt(n)) => RuntimeValue::BigInt(n.neg().sub(&BigInt::from_u64(1))),
+            (TokenType::Minus, value) => RuntimeValue::Number(-to_number(value, right.span())?),
+            (TokenType::Plus, value) => RuntimeValue::Number(to_number(value, right.span())?),
+            (TokenType::Tilde, value) => RuntimeValue::Number(!to_int32(to_number(value, right.span())?) as f64),
+            (_, value) => {
+                return Err(internal_error(format!("invalid unary operator: {:?}", operator), span)
+                    .with_label(right.span(), format!("this is {}", value.type_name()))
+                    .into())
+            }
+        };
+
+        Ok(result)
+    }
+
+    /// `delete target`, whether there is no such property or binding anymore.
+    fn delete(&mut self, target: &Expression) -> Result<RuntimeValue, Exception> {
+        let deleted = match target {
+            Expression::Grouping { expression, .. } => return self.delete(expression),
+            // declared bindings can't be deleted
+            Expression::Identifier { name, .. } => {
+                self.environment.get(name).is_none() && self.environment.get_function(name).is_none()
+            }
+            Expression::Member { object, property, span } => match self.evalutate_expression(object)? {
+                RuntimeValue::Null | RuntimeValue::Undefined => {
+                    return Err(type_error("Cannot convert undefined or null to object", *span).into())
+                }
+                // error objects can't be changed yet, their own properties stay
+                RuntimeValue::Error(_) => !matches!(property.as_str(), "message" | "stack"),
+                // primitives have no own properties yet
+                _ => true,
+            },
+            _ => {
+                self.evalutate_expression(target)?;
+                true
+            }
+        };
+
+        Ok(RuntimeValue::Boolean(deleted))
+    }
+
+    fn binary_operation(
+        &self,
+        left_value: RuntimeValue,
+        left_span: Span,
+        operator: &TokenType,
+        right_value: RuntimeValue,
+        right_span: Span,
+        span: Span,
+    ) -> Result<RuntimeValue, Diagnostic> {
+        let (left_primitive, right_primitive) = (to_primitive(&left_value), to_primitive(&right_value));
+        // `+` concatenates when either side is a string, every other operator works on numerics
+        if *operator == TokenType::Plus
+            && (matches!(left_primitive, RuntimeValue::String(_)) || matches!(right_primitive, RuntimeValue::String(_)))
+        {
+            return Ok(RuntimeValue::String(format!("{}{}", left_primitive, right_primitive)));
+        }
+
+        match (to_numeric(&left_primitive), operator, to_numeric(&right_primitive)) {
+            (RuntimeValue::Number(a), TokenType::Star, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(a * b))
+            }
+            (RuntimeValue::Number(a), TokenType::Slash, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(a / b))
+            }
+            (RuntimeValue::Number(a), TokenType::Plus, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(a + b))
+            }
+            (RuntimeValue::Number(a), TokenType::Minus, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(a - b))
+            }
+            // f64 `%` truncates like JS does, the result has the sign of the dividend
+            (RuntimeValue::Number(a), TokenType::Percent, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(a % b))
+            }
+            (RuntimeValue::Number(a), TokenType::StarStar, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(number_pow(a, b)))
+            }
+            (RuntimeValue::Number(a), TokenType::LeftShift, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(to_int32(a).wrapping_shl(to_uint32(b)) as f64))
+            }
+            (RuntimeValue::Number(a), TokenType::RightShift, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(to_int32(a).wrapping_shr(to_uint32(b)) as f64))
+            }
+            (RuntimeValue::Number(a), TokenType::UnsignedRightShift, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number(to_uint32(a).wrapping_shr(to_uint32(b)) as f64))
+            }
+            (RuntimeValue::Number(a), TokenType::Ampersand, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number((to_int32(a) & to_int32(b)) as f64))
+            }
+            (RuntimeValue::Number(a), TokenType::Pipe, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number((to_int32(a) | to_int32(b)) as f64))
+            }
+            (RuntimeValue::Number(a), TokenType::Caret, RuntimeValue::Number(b)) => {
+                Ok(RuntimeValue::Number((to_int32(a) ^ to_int32(b)) as f64))
+            }
+            (RuntimeValue::BigInt(a), _, RuntimeValue::BigInt(b)) => {
+                self.bigint_arithmetic(&a, operator, &b, span)
+            }
+            (RuntimeValue::BigInt(_), _, _) | (_, _, RuntimeValue::BigInt(_)) => {
+                Err(type_error("Cannot mix BigInt and other types, use explicit conversions", span)
+                    .with_label(left_span, format!("this is {}", left_value.type_name()))
+                    .with_label(right_span, format!("this is {}", right_value.type_name())))
+            }
+            _ => Err(internal_error(
+                format!("unhandled binary expression: {:?} {:?} {:?}", left_value, operator, right_value),
+                span,
+            )
+            .with_label(left_span, format!("this is {}", left_value.type_name()))
+            .with_label(right_span, format!("this is {}", right_value.type_name()))),
+        }
+    }
+
+    fn declare(&mut self, name: &str, value: RuntimeValue, is_const: bool, span: Span) -> Result<(), Diagnostic> {
+        // the parser reports declaring a name twice, constants are kept anyway
+        if !self.environment.declare(name.to_string(), value, is_const) {
+            return Err(internal_error(format!("constant {} declared again", name), span));
+        }
+        Ok(())
+    }
+
+    fn get_variable(&self, name: &str, span: Span) -> Result<RuntimeValue, Diagnostic> {
+        self.environment
+            .get(name)
+            .ok_or_else(|| reference_error(format!("{} is not defined", name), span))
+    }
+
+    fn assign_variable(&mut self, name: &str, value: RuntimeValue, span: Span) -> Result<RuntimeValue, Diagnostic> {
+        if self.environment.is_constant(name) {
+            return Err(type_error("Assignment to constant variable.", span)
+                .with_note(format!("{} is declared with const", name)));
+        }
+
+        if !self.environment.assign(name, value.clone()) {
+            return Err(reference_error(format!("{} is not defined", name), span));
+        }
+        println!("runtime>: assigned {:?} = {:?}", name, value);
+        Ok(value)
+    }
+
+    fn call_function(&mut self, callee: &String, args: &[Expression], span: Span) -> Result<RuntimeValue, Exception> {
+      let (function, scope) = match self.environment.get_function(callee) {
+        Some(function) => function,
+        // a variable is never a function, they aren't values yet
+        None if self.environment.get(callee).is_some() => {
+          return Err(type_error(format!("{} is not a function", callee), span).into());
+        }
+        None => {
+          let builtin = builtins::get(callee).ok_or_else(|| {
+            Diagnostic::error("E0202", format!("function {:?} is not defined", callee), span)
+              .with_note("functions must be declared before use (hoisting is not supported)")
+          })?;
+          let evaluated_args = self.evaluate_arguments(args)?;
+          return Ok(builtin(&evaluated_args, span)?);
+        }
+      };
       let evaluated_args = self.evaluate_arguments(args)?;
+      if self.call_depth >= MAX_CALL_DEPTH {
+        return Err(range_error("Maximum call stack size exceeded", span).into());
+      }
       println!("runtime>: function {:?} called with {:?}", callee, evaluated_args);
       
-      let (body, params) = match function {
+      let (body, params) = match &function {
         Statement::FunctionDeclaration { body, params, .. } => (body, params),
-        _ => return Err(String::from("expected Statement::FunctionDeclaration as function"))
+        _ => return Err(internal_error("expected Statement::FunctionDeclaration as function", span).into())
       };
       
-      let mut local_scope = Environment::new();
-      for (key, value) in &self.environment.functions {
-        local_scope.functions.insert(key.clone(), value.clone());
-      }
+      // the body sees the scope the function was declared in, not the caller's
+      let mut local_scope = Environment::enclosed_by(scope);
       self.bind_params(params, &evaluated_args, &mut local_scope);
       
       let result = self.execute_function_body(local_scope, body)?;
@@ -159,116 +648,125 @@ impl Runtime {
       Ok(result)
     }
 
-    fn get_function(&self, callee: &String) -> Result<&Statement, String> {
-      self.environment.functions
-        .get(callee)
-        .ok_or(format!("function {:?} is not defined (hoisting is not supported)", callee))
+    /// `object.name(args)`, only primitives have methods so far, the built-in ones.
+    fn call_method(&mut self, object: &Expression, name: &str, args: &[Expression], span: Span) -> Result<RuntimeValue, Exception> {
+      let value = self.evalutate_expression(object)?;
+      if matches!(value, RuntimeValue::Null | RuntimeValue::Undefined) {
+        return Err(type_error(format!("Cannot read properties of {} (reading '{}')", value, name), span).into());
+      }
+
+      let Some(method) = builtins::method(&value, name) else {
+        let receiver = match object {
+          Expression::Identifier { name, .. } => name.clone(),
+          _ => value.to_string(),
+        };
+        return Err(type_error(format!("{}.{} is not a function", receiver, name), span).into());
+      };
+      let evaluated_args = self.evaluate_arguments(args)?;
+      Ok(method(&value, &evaluated_args, span)?)
     }
 
-    fn evaluate_arguments(&self, args: &Vec<Expression>) -> Result<Vec<RuntimeValue>, String> {
+    fn evaluate_arguments(&mut self, args: &[Expression]) -> Result<Vec<RuntimeValue>, Exception> {
       args.iter()
         .map(|arg| self.evalutate_expression(arg))
         .collect()
     }
 
-    fn bind_params(&self, params: &Vec<String>, values: &Vec<RuntimeValue>, environment: &mut Environment) {
+    fn bind_params(&self, params: &[String], values: &[RuntimeValue], environment: &mut Environment) {
       for (i, param) in params.iter().enumerate() {
         let value = values.get(i).cloned().unwrap_or(RuntimeValue::Undefined);
-        environment.variables.insert(param.clone(), value);
+        environment.declare(param.clone(), value, false);
       }
     } 
 
-    fn execute_function_body(&self, local_scope: Environment, body: &Vec<Statement>) -> Result<RuntimeValue, String> {
-      let mut runtime = Runtime { environment: local_scope };
+    fn execute_function_body(&self, local_scope: Environment, body: &[Statement]) -> Result<RuntimeValue, Exception> {
+      // the body gets a runtime one call deeper, leaving it in any way returns to this depth
+      let mut runtime = Runtime { environment: local_scope, call_depth: self.call_depth + 1 };
 
-      for statement in body.iter() {
-        if let Statement::Return { expression } = statement {
-            return runtime.evalutate_expression(expression);
-        }
-        
-        if let Err(error) = runtime.evaluate_statement(statement.clone()) {
-          return Err(error);
-        }
+      match runtime.evaluate_statements(body)? {
+        Completion::Return(value) => Ok(value),
+        // the parser doesn't let `break` and `continue` out of a function
+        _ => Ok(RuntimeValue::Undefined),
       }
-    
-      Ok(RuntimeValue::Undefined)
     }
 
-    fn compare_numbers(&self, a: f64, b: f64, operator: &TokenType) -> Result<RuntimeValue, String> {
-        match operator {
-            TokenType::Equal | TokenType::StrictEqual => Ok(RuntimeValue::Boolean(a == b)),
-            TokenType::NotEqual | TokenType::StrictNotEqual => Ok(RuntimeValue::Boolean(a != b)),
-            TokenType::GreaterThan => Ok(RuntimeValue::Boolean(a > b)),
-            TokenType::GreaterThanOrEqual => Ok(RuntimeValue::Boolean(a >= b)),
-            TokenType::LessThan => Ok(RuntimeValue::Boolean(a < b)),
-            TokenType::LessThanOrEqual => Ok(RuntimeValue::Boolean(a <= b)),
-            _ => Err(format!("invalid operator for numbers: {:?}", operator)),
-        }
+    fn bigint_arithmetic(&self, a: &BigInt, operator: &TokenType, b: &BigInt, span: Span) -> Result<RuntimeValue, Diagnostic> {
+        let result = match operator {
+            TokenType::Plus => a.add(b),
+            TokenType::Minus => a.sub(b),
+            TokenType::Star => a.mul(b),
+            TokenType::Slash => match a.div_rem(b) {
+                Some((quotient, _)) => quotient,
+                None => return Err(range_error("Division by zero", span)),
+            },
+            TokenType::Percent => match a.div_rem(b) {
+                Some((_, remainder)) => remainder,
+                None => return Err(range_error("Division by zero", span)),
+            },
+            TokenType::StarStar if b.is_negative() => return Err(range_error("Exponent must be non-negative", span)),
+            TokenType::StarStar => a.pow(b).ok_or_else(|| range_error("Maximum BigInt size exceeded", span))?,
+            TokenType::LeftShift => a.shl(b).ok_or_else(|| range_error("Maximum BigInt size exceeded", span))?,
+            TokenType::RightShift => a.shr(b).ok_or_else(|| range_error("Maximum BigInt size exceeded", span))?,
+            TokenType::UnsignedRightShift => {
+                return Err(type_error("BigInts have no unsigned right shift, use >> instead", span))
+            }
+            TokenType::Ampersand => a.bitand(b),
+            TokenType::Pipe => a.bitor(b),
+            TokenType::Caret => a.bitxor(b),
+            _ => return Err(internal_error(format!("invalid operator for bigints: {:?}", operator), span)),
+        };
+
+        Ok(RuntimeValue::BigInt(result))
     }
 
-    fn compare_booleans(&self, a: bool, b: bool, operator: &TokenType) -> Result<RuntimeValue, String> {
-        match operator {
-            TokenType::Equal | TokenType::StrictEqual => Ok(RuntimeValue::Boolean(a == b)),
-            TokenType::NotEqual | TokenType::StrictNotEqual => Ok(RuntimeValue::Boolean(a != b)),
-            TokenType::GreaterThan => Ok(RuntimeValue::Boolean(a > b)),
-            TokenType::GreaterThanOrEqual => Ok(RuntimeValue::Boolean(a >= b)),
-            TokenType::LessThan => Ok(RuntimeValue::Boolean(a < b)),
-            TokenType::LessThanOrEqual => Ok(RuntimeValue::Boolean(a <= b)),
-            _ => Err(format!("invalid operator for booleans: {:?}", operator)),
-        }
+}
+
+/// What a loop does after its body completed with `completion`: `None` to go
+/// on with the next iteration, or the completion of the whole loop.
+fn loop_exit(completion: Completion, labels: &[String]) -> Option<Completion> {
+    match completion {
+        Completion::Normal | Completion::Continue(None) => None,
+        Completion::Continue(Some(label)) if labels.contains(&label) => None,
+        Completion::Break(None) => Some(Completion::Normal),
+        Completion::Break(Some(label)) if labels.contains(&label) => Some(Completion::Normal),
+        completion => Some(completion),
     }
+}
 
-    fn compare_strings(&self, a: &str, b: &str, operator: &TokenType) -> Result<RuntimeValue, String> {
-        match operator {
-            TokenType::Equal | TokenType::StrictEqual => Ok(RuntimeValue::Boolean(a == b)),
-            TokenType::NotEqual | TokenType::StrictNotEqual => Ok(RuntimeValue::Boolean(a != b)),
-            TokenType::GreaterThan => Ok(RuntimeValue::Boolean(a > b)),
-            TokenType::GreaterThanOrEqual => Ok(RuntimeValue::Boolean(a >= b)),
-            TokenType::LessThan => Ok(RuntimeValue::Boolean(a < b)),
-            TokenType::LessThanOrEqual => Ok(RuntimeValue::Boolean(a <= b)),
-            _ => Err(format!("invalid operator for strings: {:?}", operator)),
-        }
+/// Whether `&&`, `||` or `??` results in its left operand `value`, without evaluating the right one.
+fn short_circuits(operator: &TokenType, value: &RuntimeValue) -> bool {
+    match operator {
+        TokenType::AmpersandAmpersand => !to_boolean(value),
+        TokenType::PipePipe => to_boolean(value),
+        _ => !matches!(value, RuntimeValue::Null | RuntimeValue::Undefined),
     }
+}
 
-    fn compare_undefinds(&self, operator: &TokenType) -> Result<RuntimeValue, String> {
-        match operator {
-            TokenType::Equal | TokenType::StrictEqual => Ok(RuntimeValue::Boolean(true)),
-            TokenType::NotEqual | TokenType::StrictNotEqual | TokenType::GreaterThan => {
-                Ok(RuntimeValue::Boolean(false))
-            }
-            TokenType::GreaterThanOrEqual | TokenType::LessThan | TokenType::LessThanOrEqual => {
-                Ok(RuntimeValue::Boolean(false))
-            }
-            _ => Err(format!("invalid operator for undefinds: {:?}", operator)),
-        }
+/// The values `for...of` goes through. Only strings are iterable so far, by code point.
+// TODO: drive this with the iterator protocol (`Symbol.iterator`, `next()`, and
+// `return()` when the loop is left early) once objects and arrays exist
+fn for_of_values(value: RuntimeValue, span: Span) -> Result<Vec<RuntimeValue>, Diagnostic> {
+    match value {
+        RuntimeValue::String(string) => Ok(string.chars().map(|c| RuntimeValue::String(c.to_string())).collect()),
+        value => Err(type_error(format!("{} is not iterable", value), span)),
     }
+}
 
-    fn compare_nulls(&self, operator: &TokenType) -> Result<RuntimeValue, String> {
-        match operator {
-            TokenType::Equal | TokenType::StrictNotEqual => Ok(RuntimeValue::Boolean(true)),
-            TokenType::NotEqual | TokenType::StrictEqual | TokenType::GreaterThan => {
-                Ok(RuntimeValue::Boolean(false))
-            }
-            TokenType::GreaterThanOrEqual | TokenType::LessThan | TokenType::LessThanOrEqual => {
-                Ok(RuntimeValue::Boolean(false))
-            }
-            _ => Err(format!("invalid operator for nulls: {:?}", operator)),
-        }
+/// The keys `for...in` goes through, the indices of a string and nothing for other primitives.
+// TODO: enumerate the string keys of objects up the prototype chain once they exist
+fn for_in_keys(value: &RuntimeValue) -> Vec<RuntimeValue> {
+    match value {
+        RuntimeValue::String(string) => (0..string.encode_utf16().count())
+            .map(|index| RuntimeValue::String(index.to_string()))
+            .collect(),
+        _ => vec![],
     }
+}
 
-    fn compare_null_undefined(&self, operator: &TokenType) -> Result<RuntimeValue, String> {
-        match operator {
-            TokenType::Equal | TokenType::StrictNotEqual => Ok(RuntimeValue::Boolean(true)),
-            TokenType::NotEqual | TokenType::StrictEqual | TokenType::GreaterThan => {
-                Ok(RuntimeValue::Boolean(false))
-            }
-            TokenType::GreaterThanOrEqual | TokenType::LessThan | TokenType::LessThanOrEqual => {
-                Ok(RuntimeValue::Boolean(false))
-            }
-            _ => Err(format!(
-                "invalid operator for null and undefined: {:?}",
-                operator
-            )),
-        }
+/// JS `**`, which unlike `powf` is NaN for a NaN exponent and for `1 ** Infinity`.
+fn number_pow(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return f64::NAN;
     }
-}
\ No newline at end of file
+    base.powf(exponent)
+}
@@ -1,20 +1,133 @@
-use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use super::values::RuntimeValue;
 use crate::common::ast::Statement;
 
+/// The scope being run and, through its parents, every scope around it.
 pub struct Environment {
-    pub variables: HashMap<String, RuntimeValue>,
-    pub constants: HashSet<String>,
+    scope: Rc<RefCell<Scope>>,
+}
+
+/// The bindings of the global code, a function call or a block. Scopes are
+/// shared: a function's body runs in a scope inside the one it was declared in.
+pub struct Scope {
+    variables: HashMap<String, Binding>,
     // TODO: move out Statement::FunctionDeclaration from enum for less ambiguity
-    pub functions: HashMap<String, Statement>,
+    functions: HashMap<String, Statement>,
+    parent: Option<Rc<RefCell<Scope>>>,
 }
 
-impl Environment {
-    pub fn new() -> Self {
-        Self {
+struct Binding {
+    value: RuntimeValue,
+    is_const: bool,
+}
+
+impl Scope {
+    fn new(parent: Option<Rc<RefCell<Scope>>>) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
             variables: HashMap::new(),
-            constants: HashSet::new(),
             functions: HashMap::new(),
+            parent,
+        }))
+    }
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self { scope: Scope::new(None) }
+    }
+
+    /// A new scope inside `parent`, for a call of a function declared there.
+    pub fn enclosed_by(parent: Rc<RefCell<Scope>>) -> Self {
+        Self { scope: Scope::new(Some(parent)) }
+    }
+
+    /// Starts a block scope inside the current one, until the matching `exit_block`.
+    pub fn enter_block(&mut self) {
+        self.scope = Scope::new(Some(self.scope.clone()));
+    }
+
+    pub fn exit_block(&mut self) {
+        let parent = self.scope.borrow().parent.clone();
+        if let Some(parent) = parent {
+            self.scope = parent;
+        }
+    }
+
+    /// Replaces the innermost block by a new one, with copies of the `names`
+    /// bindings as they are now. Used for the iterations of a `for`.
+    pub fn renew_block(&mut self, names: &[String]) {
+        let values: Vec<RuntimeValue> = names
+            .iter()
+            .map(|name| self.get(name).unwrap_or(RuntimeValue::Undefined))
+            .collect();
+
+        self.exit_block();
+        self.enter_block();
+        for (name, value) in names.iter().zip(values) {
+            self.declare(name.clone(), value, false);
+        }
+    }
+
+    /// Declares `name` in the current scope, false when a constant there has that name already.
+    pub fn declare(&mut self, name: String, value: RuntimeValue, is_const: bool) -> bool {
+        let mut scope = self.scope.borrow_mut();
+        if scope.variables.get(&name).is_some_and(|binding| binding.is_const) {
+            return false;
+        }
+        scope.variables.insert(name, Binding { value, is_const });
+        true
+    }
+
+    pub fn declare_function(&mut self, name: String, declaration: Statement) {
+        self.scope.borrow_mut().functions.insert(name, declaration);
+    }
+
+    /// The value of the innermost variable called `name`.
+    pub fn get(&self, name: &str) -> Option<RuntimeValue> {
+        self.find(|scope| scope.variables.get(name).map(|binding| binding.value.clone()))
+    }
+
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.find(|scope| scope.variables.get(name).map(|binding| binding.is_const)).unwrap_or(false)
+    }
+
+    /// Changes the innermost variable called `name`, false when there is none.
+    pub fn assign(&mut self, name: &str, value: RuntimeValue) -> bool {
+        let mut scope = Some(self.scope.clone());
+        while let Some(current) = scope {
+            if let Some(binding) = current.borrow_mut().variables.get_mut(name) {
+                binding.value = value;
+                return true;
+            }
+            scope = current.borrow().parent.clone();
+        }
+        false
+    }
+
+    /// The innermost function called `name`, with the scope it was declared in.
+    /// Functions don't hold on to that scope themselves, it holds them.
+    pub fn get_function(&self, name: &str) -> Option<(Statement, Rc<RefCell<Scope>>)> {
+        let mut scope = Some(self.scope.clone());
+        while let Some(current) = scope {
+            if let Some(declaration) = current.borrow().functions.get(name) {
+                return Some((declaration.clone(), current.clone()));
+            }
+            scope = current.borrow().parent.clone();
+        }
+        None
+    }
+
+    /// The first result of `lookup` from the current scope outwards.
+    fn find<T>(&self, lookup: impl Fn(&Scope) -> Option<T>) -> Option<T> {
+        let mut scope = Some(self.scope.clone());
+        while let Some(current) = scope {
+            if let Some(found) = lookup(&current.borrow()) {
+                return Some(found);
+            }
+            scope = current.borrow().parent.clone();
         }
+        None
     }
 }
@@ -0,0 +1,64 @@
+//! Errors that JS would throw. They are created as diagnostics, with a message
+//! starting with the JS error name, and become catchable `Exception`s.
+
+use std::rc::Rc;
+use super::values::{ErrorObject, RuntimeValue};
+use crate::common::{Diagnostic, Span};
+
+/// A thrown JS value, with the diagnostic to report if nothing catches it.
+/// Internal errors have no value, scripts can't catch them.
+#[derive(Debug)]
+pub struct Exception {
+    pub value: Option<RuntimeValue>,
+    pub diagnostic: Diagnostic,
+}
+
+impl Exception {
+    pub fn thrown(value: RuntimeValue, span: Span) -> Self {
+        let diagnostic = Diagnostic::error("E0208", format!("Uncaught {}", value), span);
+        Self { value: Some(value), diagnostic }
+    }
+}
+
+/// Runtime errors turn into the matching JS error object.
+impl From<Diagnostic> for Exception {
+    fn from(diagnostic: Diagnostic) -> Self {
+        let name = match diagnostic.code {
+            "E0209" => return Self { value: None, diagnostic },
+            "E0202" | "E0207" => "ReferenceError",
+            "E0206" => "SyntaxError",
+            "E0205" => "RangeError",
+            _ => "TypeError",
+        };
+        let message = diagnostic
+            .message
+            .strip_prefix(name)
+            .and_then(|message| message.strip_prefix(": "))
+            .unwrap_or(&diagnostic.message);
+
+        let error = ErrorObject::new(name, message, diagnostic.span);
+        Self { value: Some(RuntimeValue::Error(Rc::new(error))), diagnostic }
+    }
+}
+
+pub fn type_error(message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::error("E0201", format!("TypeError: {}", message.into()), span)
+}
+
+pub fn range_error(message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::error("E0205", format!("RangeError: {}", message.into()), span)
+}
+
+pub fn syntax_error(message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::error("E0206", format!("SyntaxError: {}", message.into()), span)
+}
+
+pub fn reference_error(message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::error("E0207", format!("ReferenceError: {}", message.into()), span)
+}
+
+/// A bug in the interpreter rather than in the script, like an AST the parser
+/// doesn't produce.
+pub fn internal_error(message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::error("E0209", format!("internal error: {}", message.into()), span)
+}
@@ -0,0 +1,219 @@
+//! Number to string conversions: Number::toString and the digits that
+//! `toString(radix)`, `toFixed`, `toExponential` and `toPrecision` produce.
+
+use std::cmp::Ordering;
+use crate::common::BigInt;
+
+/// Number::toString, the shortest digits that round-trip, in exponent form
+/// from 1e21 on and below 1e-6.
+pub fn to_string(value: f64) -> String {
+    if value.is_nan() {
+        return String::from("NaN");
+    }
+    // covers -0 too
+    if value == 0.0 {
+        return String::from("0");
+    }
+    if value < 0.0 {
+        return format!("-{}", to_string(-value));
+    }
+    if value.is_infinite() {
+        return String::from("Infinity");
+    }
+
+    let (digits, point) = shortest_digits(value);
+    let length = digits.len() as i32;
+    if length <= point && point <= 21 {
+        format!("{}{}", digits, "0".repeat((point - length) as usize))
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+        format!("{}.{}", integer, fraction)
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        exponential(&digits, point - 1)
+    }
+}
+
+/// Number::toString with a radix other than 10, digits of the fraction are
+/// written until the rest can't change the value anymore.
+pub fn to_string_radix(value: f64, radix: u32) -> String {
+    if radix == 10 || !value.is_finite() {
+        return to_string(value);
+    }
+    if value == 0.0 {
+        return String::from("0");
+    }
+
+    let (sign, value) = if value < 0.0 { ("-", -value) } else { ("", value) };
+    let base = radix as f64;
+    let mut integer = value.floor();
+    let mut fraction = value - integer;
+    // half the distance to the next double, anything smaller doesn't tell them apart
+    let mut delta = (0.5 * (value.next_up() - value)).max(0f64.next_up());
+
+    let mut fraction_digits = vec![];
+    while fraction >= delta {
+        fraction *= base;
+        delta *= base;
+        let digit = fraction as u32;
+        fraction_digits.push(digit);
+        fraction -= digit as f64;
+
+        // round half to even, carrying into the digits before when rounding up
+        if (fraction > 0.5 || (fraction == 0.5 && digit & 1 == 1)) && fraction + delta > 1.0 {
+            loop {
+                match fraction_digits.pop() {
+                    Some(digit) if digit + 1 < radix => {
+                        fraction_digits.push(digit + 1);
+                        break;
+                    }
+                    Some(_) => continue,
+                    None => {
+                        integer += 1.0;
+                        break;
+                    }
+                }
+            }
+            break;
+        }
+    }
+
+    // digits below the precision of a double are zeros
+    let mut integer_digits = vec![];
+    while integer / base >= 2f64.powi(53) {
+        integer /= base;
+        integer_digits.push(0);
+    }
+    loop {
+        let remainder = integer % base;
+        integer_digits.push(remainder as u32);
+        integer = (integer - remainder) / base;
+        if integer <= 0.0 {
+            break;
+        }
+    }
+
+    let to_char = |digit: &u32| std::char::from_digit(*digit, radix).unwrap_or('?');
+    let mut result: String = sign.to_string();
+    result.extend(integer_digits.iter().rev().map(to_char));
+    if !fraction_digits.is_empty() {
+        result.push('.');
+        result.extend(fraction_digits.iter().map(to_char));
+    }
+    result
+}
+
+/// Number.prototype.toFixed for a finite value below 1e21, ties round up.
+pub fn to_fixed(value: f64, fraction_digits: usize) -> String {
+    let (sign, value) = if value < 0.0 { ("-", -value) } else { ("", value) };
+    let digits = scaled_round(value, fraction_digits as i32).to_string();
+    if fraction_digits == 0 {
+        return format!("{}{}", sign, digits);
+    }
+
+    let digits = format!("{:0>width$}", digits, width = fraction_digits + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - fraction_digits);
+    format!("{}{}.{}", sign, integer, fraction)
+}
+
+/// Number.prototype.toExponential for a finite value, with as many fraction
+/// digits as needed to round-trip when `fraction_digits` is `None`.
+pub fn to_exponential(value: f64, fraction_digits: Option<usize>) -> String {
+    let (sign, value) = if value < 0.0 { ("-", -value) } else { ("", value) };
+    let (digits, exponent) = if value == 0.0 {
+        ("0".repeat(fraction_digits.unwrap_or(0) + 1), 0)
+    } else if let Some(fraction_digits) = fraction_digits {
+        precise_digits(value, fraction_digits + 1)
+    } else {
+        let (digits, point) = shortest_digits(value);
+        (digits, point - 1)
+    };
+
+    format!("{}{}", sign, exponential(&digits, exponent))
+}
+
+/// Number.prototype.toPrecision for a finite value, in exponent form when the
+/// exponent is below -6 or doesn't fit into `precision` digits.
+pub fn to_precision(value: f64, precision: usize) -> String {
+    let (sign, value) = if value < 0.0 { ("-", -value) } else { ("", value) };
+    let (digits, exponent) = if value == 0.0 {
+        ("0".repeat(precision), 0)
+    } else {
+        precise_digits(value, precision)
+    };
+
+    let result = if exponent < -6 || exponent >= precision as i32 {
+        exponential(&digits, exponent)
+    } else if exponent >= 0 {
+        let (integer, fraction) = digits.split_at(exponent as usize + 1);
+        if fraction.is_empty() { integer.to_string() } else { format!("{}.{}", integer, fraction) }
+    } else {
+        format!("0.{}{}", "0".repeat((-exponent - 1) as usize), digits)
+    };
+    format!("{}{}", sign, result)
+}
+
+/// `d.ddde+x` for the digits of a value whose first digit is at `10^exponent`.
+fn exponential(digits: &str, exponent: i32) -> String {
+    let (first, rest) = digits.split_at(1);
+    let mantissa = if rest.is_empty() { first.to_string() } else { format!("{}.{}", first, rest) };
+    format!("{}e{}{}", mantissa, if exponent < 0 { '-' } else { '+' }, exponent.abs())
+}
+
+/// The shortest digits that round-trip to a positive `value`, and where the
+/// decimal point goes: `value` is `0.digits * 10^point`.
+fn shortest_digits(value: f64) -> (String, i32) {
+    // Rust's exponent form already has the shortest round-trip digits, `1.5e-7`
+    let formatted = format!("{:e}", value);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let digits = mantissa.replace('.', "");
+    (digits, exponent.parse::<i32>().unwrap_or(0) + 1)
+}
+
+/// A positive `value` rounded to `precision` significant digits, ties up, and
+/// the exponent of the first digit.
+fn precise_digits(value: f64, precision: usize) -> (String, i32) {
+    // the logarithm is at most one off, the number of digits tells which way
+    let mut exponent = value.log10().floor() as i32;
+    loop {
+        let digits = scaled_round(value, precision as i32 - 1 - exponent).to_string();
+        match digits.len().cmp(&precision) {
+            Ordering::Greater => exponent += 1,
+            Ordering::Less => exponent -= 1,
+            Ordering::Equal => return (digits, exponent),
+        }
+    }
+}
+
+/// The exact value of a non-negative `value * 10^scale`, rounded to an integer with ties up.
+fn scaled_round(value: f64, scale: i32) -> BigInt {
+    let bits = value.to_bits();
+    let (biased_exponent, fraction) = ((bits >> 52) & 0x7ff, bits & ((1 << 52) - 1));
+    // value is mantissa * 2^exponent
+    let (mantissa, exponent) = match biased_exponent {
+        0 => (fraction, -1074),
+        _ => (fraction | 1 << 52, biased_exponent as i64 - 1075),
+    };
+
+    let one = BigInt::from_u64(1);
+    let power_of_ten = BigInt::from_u64(10).pow(&BigInt::from_u64(scale.unsigned_abs() as u64)).unwrap_or_else(BigInt::zero);
+    let power_of_two = BigInt::from_u64(exponent.unsigned_abs());
+    let (mut numerator, mut denominator) = (BigInt::from_u64(mantissa), one.clone());
+    if scale >= 0 {
+        numerator = numerator.mul(&power_of_ten);
+    } else {
+        denominator = denominator.mul(&power_of_ten);
+    }
+    if exponent >= 0 {
+        numerator = numerator.shl(&power_of_two).unwrap_or_else(BigInt::zero);
+    } else {
+        denominator = denominator.shl(&power_of_two).unwrap_or_else(BigInt::zero);
+    }
+
+    match numerator.div_rem(&denominator) {
+        Some((quotient, remainder)) if remainder.add(&remainder) >= denominator => quotient.add(&one),
+        Some((quotient, _)) => quotient,
+        None => BigInt::zero(),
+    }
+}
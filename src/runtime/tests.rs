@@ -0,0 +1,1208 @@
+use super::*;
+use super::values::RuntimeValue;
+use crate::common::{ast::*, Diagnostic, Literal, Span, TokenType};
+use crate::parser::Parser;
+use crate::tokenizer::Tokenizer;
+
+fn run(source: &str) -> (Runtime, Vec<Diagnostic>) {
+    let mut tokenizer = Tokenizer::new(source.into());
+    let tokens = tokenizer.tokenize().expect("source should tokenize");
+    let (statements, errors) = Parser::new(tokens).parse();
+    assert!(errors.is_empty(), "source should parse: {:?}", errors);
+
+    let mut runtime = Runtime::new();
+    let errors = runtime.interpret(statements).err().into_iter().collect();
+    (runtime, errors)
+}
+
+fn get_variable(source: &str, name: &str) -> RuntimeValue {
+    let (runtime, errors) = run(source);
+    assert!(errors.is_empty(), "source should run: {:?}", errors);
+
+    runtime.environment.get(name).expect("variable should be declared")
+}
+
+#[test]
+fn test_template_literals() {
+    assert_eq!(
+        get_variable("let a = 2; let s = `${a} + ${a} = ${a + a}!`;", "s"),
+        RuntimeValue::String("2 + 2 = 4!".into())
+    );
+    assert_eq!(
+        get_variable("let s = `${null} ${undefined} ${true} ${1.5} ${'str'} ${1 > 2}`;", "s"),
+        RuntimeValue::String("null undefined true 1.5 str false".into())
+    );
+    assert_eq!(
+        get_variable("let s = `outer ${`inner ${1}`}`;", "s"),
+        RuntimeValue::String("outer inner 1".into())
+    );
+    assert_eq!(
+        get_variable("let s = `line 1\nline 2\\t${0 / 0}`;", "s"),
+        RuntimeValue::String("line 1\nline 2\tNaN".into())
+    );
+}
+
+#[test]
+fn test_template_literal_with_function_call() {
+    assert_eq!(
+        get_variable("function twice(x) { return x * 2; } let s = `${twice(21)}`;", "s"),
+        RuntimeValue::String("42".into())
+    );
+}
+
+fn bigint(value: &str) -> RuntimeValue {
+    RuntimeValue::BigInt(conversions::string_to_bigint(value).expect("valid bigint"))
+}
+
+fn get_error(source: &str) -> Diagnostic {
+    let (_, mut errors) = run(source);
+    assert!(!errors.is_empty(), "source should fail: {}", source);
+    errors.remove(0)
+}
+
+#[test]
+fn test_bigint_arithmetic() {
+    assert_eq!(get_variable("let x = 9007199254740993n + 2n;", "x"), bigint("9007199254740995"));
+    assert_eq!(get_variable("let x = 2n * 3n - 10n;", "x"), bigint("-4"));
+    assert_eq!(get_variable("let x = -7n / 2n;", "x"), bigint("-3"));
+    assert_eq!(get_variable("let x = `${-0n} ${10n * 1n}`;", "x"), RuntimeValue::String("0 10".into()));
+}
+
+#[test]
+fn test_bigint_comparisons() {
+    assert_eq!(get_variable("let x = 2n > 1n;", "x"), RuntimeValue::Boolean(true));
+    assert_eq!(get_variable("let x = 1n == 1;", "x"), RuntimeValue::Boolean(true));
+    assert_eq!(get_variable("let x = 1n === 1;", "x"), RuntimeValue::Boolean(false));
+    assert_eq!(get_variable("let x = 1 < 2n;", "x"), RuntimeValue::Boolean(true));
+    assert_eq!(get_variable("let x = 1n < 1.5;", "x"), RuntimeValue::Boolean(true));
+    assert_eq!(get_variable("let x = 1n <= 0 / 0;", "x"), RuntimeValue::Boolean(false));
+}
+
+#[test]
+fn test_bigint_mixed_with_number_is_type_error() {
+    let error = get_error("let x = 1n + 1;");
+    assert_eq!(error.code, "E0201");
+    assert_eq!(error.message, "TypeError: Cannot mix BigInt and other types, use explicit conversions");
+    assert_eq!(get_error("let x = 1 * 2n;").code, "E0201");
+}
+
+#[test]
+fn test_bigint_division_by_zero_is_range_error() {
+    assert_eq!(get_error("let x = 1n / 0n;").message, "RangeError: Division by zero");
+    assert_eq!(get_error("let x = 1n % 0n;").message, "RangeError: Division by zero");
+}
+
+#[test]
+fn test_bigint_remainder_exponent_and_bitwise() {
+    for (source, expected) in [
+        ("let x = -7n % 2n;", "-1"),
+        ("let x = 2n ** 100n;", "1267650600228229401496703205376"),
+        ("let x = (-2n) ** 3n;", "-8"),
+        ("let x = 1n << 70n;", "1180591620717411303424"),
+        ("let x = -5n >> 1n;", "-3"),
+        ("let x = 8n << -2n;", "2"),
+        ("let x = -6n & 3n;", "2"),
+        ("let x = -6n | 3n;", "-5"),
+        ("let x = -6n ^ 3n;", "-7"),
+    ] {
+        assert_eq!(get_variable(source, "x"), bigint(expected), "{}", source);
+    }
+
+    assert_eq!(get_error("let x = 2n ** -1n;").message, "RangeError: Exponent must be non-negative");
+    assert_eq!(get_error("let x = 2n ** 10000000000n;").message, "RangeError: Maximum BigInt size exceeded");
+    assert_eq!(
+        get_error("let x = 1n >>> 0n;").message,
+        "TypeError: BigInts have no unsigned right shift, use >> instead"
+    );
+    assert_eq!(get_error("let x = 1n & 1;").code, "E0201");
+}
+
+#[test]
+fn test_bigint_conversion() {
+    assert_eq!(get_variable("let x = BigInt(42);", "x"), bigint("42"));
+    assert_eq!(get_variable("let x = BigInt(-1e21);", "x"), bigint("-1000000000000000000000"));
+    assert_eq!(get_variable("let x = BigInt(true);", "x"), bigint("1"));
+    assert_eq!(get_variable("let x = BigInt(' 0x1F ');", "x"), bigint("31"));
+    assert_eq!(get_variable("let x = BigInt('-12');", "x"), bigint("-12"));
+    assert_eq!(get_variable("let x = BigInt('');", "x"), bigint("0"));
+    assert_eq!(get_variable("let x = BigInt(5n);", "x"), bigint("5"));
+
+    assert_eq!(get_error("let x = BigInt(1.5);").code, "E0205");
+    assert_eq!(get_error("let x = BigInt('1.5');").code, "E0206");
+    assert_eq!(get_error("let x = BigInt('-0x1');").code, "E0206");
+    assert_eq!(get_error("let x = BigInt();").message, "TypeError: Cannot convert undefined to a BigInt");
+}
+
+#[test]
+fn test_user_function_shadows_builtin() {
+    assert_eq!(
+        get_variable("function BigInt(x) { return x; } let x = BigInt(1);", "x"),
+        RuntimeValue::Number(1.0)
+    );
+}
+
+#[test]
+fn test_functions_see_global_variables() {
+    let source = "let total = 1; function add(n) { total += n; return total; } add(2); let result = add(3);";
+    assert_eq!(get_variable(source, "total"), RuntimeValue::Number(6.0));
+    assert_eq!(get_variable(source, "result"), RuntimeValue::Number(6.0));
+
+    // variables declared after the function are visible once it is called
+    let source = "function get() { return later; } let later = 'set'; let x = get();";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::String("set".into()));
+
+    let error = get_error("const c = 1; function f() { c = 2; } f();");
+    assert_eq!(error.message, "TypeError: Assignment to constant variable.");
+}
+
+#[test]
+fn test_closures_capture_their_defining_scope() {
+    let source = "
+        function counter(start) {
+            let count = start;
+            function increment() { count += 1; return count; }
+            increment();
+            increment();
+            return increment();
+        }
+        let x = counter(10);";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::Number(13.0));
+
+    // the body sees where the function was declared, not where it is called from
+    let source = "
+        let name = 'global';
+        function read() { return name; }
+        function call() { let name = 'local'; return read(); }
+        let x = call();";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::String("global".into()));
+
+    // every call gets its own scope
+    let source = "
+        function square(n) { let result = n * n; function get() { return result; } return get(); }
+        let x = square(3) + square(4);";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::Number(25.0));
+}
+
+#[test]
+fn test_call_stack_overflow_is_catchable_range_error() {
+    // as deep as the limit goes, which needs the stack the CLI runs with
+    let test = || {
+        let error = get_error("function f() { return f(); } f();");
+        assert_eq!(error.message, "RangeError: Maximum call stack size exceeded");
+
+        let source = "
+            let depth = 0; let name = '';
+            function down() { depth += 1; return 1 + down(); }
+            try { down(); } catch (e) { name = e.name; }
+            function count(n) { return n == 0 ? 0 : 1 + count(n - 1); }
+            let x = count(100);";
+        assert_eq!(get_variable(source, "name"), RuntimeValue::String("RangeError".into()));
+        assert_eq!(get_variable(source, "depth"), RuntimeValue::Number(2000.0));
+        // leaving the calls by the exception gets back to the depth of the caller
+        assert_eq!(get_variable(source, "x"), RuntimeValue::Number(100.0));
+    };
+    let thread = std::thread::Builder::new().stack_size(STACK_SIZE).spawn(test).expect("thread should start");
+    assert!(thread.join().is_ok());
+}
+
+#[test]
+fn test_nested_functions() {
+    let source = "
+        function a(x) {
+            function b(y) {
+                function c(z) { return x + y + z; }
+                return c(3);
+            }
+            return b(2);
+        }
+        let x = a(1);";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::Number(6.0));
+
+    let source = "function f() { if (true) { function g() { return 1; } return g(); } } let x = f();";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::Number(1.0));
+
+    let source = "function fib(n) { function inner(k) { return k < 2 ? k : fib(k - 1) + fib(k - 2); } return inner(n); } let x = fib(10);";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::Number(55.0));
+
+    // inner functions and the variables of a call don't outlive it
+    assert_eq!(get_error("function f() { function g() {} } f(); g();").code, "E0202");
+    assert_eq!(get_error("function f(a) { let b = 1; } f(1); b += a;").code, "E0207");
+}
+
+#[test]
+fn test_inner_declarations_shadow_outer_ones() {
+    let source = "
+        let x = 'outer';
+        function f() { return 'outer'; }
+        function g() {
+            let x = 'inner';
+            function f() { return x; }
+            return f();
+        }
+        let inner = g();
+        let outer = f();";
+    assert_eq!(get_variable(source, "inner"), RuntimeValue::String("inner".into()));
+    assert_eq!(get_variable(source, "outer"), RuntimeValue::String("outer".into()));
+    assert_eq!(get_variable(source, "x"), RuntimeValue::String("outer".into()));
+}
+
+#[test]
+fn test_assignment() {
+    assert_eq!(get_variable("let x = 1; x = 2;", "x"), RuntimeValue::Number(2.0));
+    assert_eq!(get_variable("let x; let y = x = 3;", "y"), RuntimeValue::Number(3.0));
+    assert_eq!(get_variable("let a = 1; let b = 2; a = b = 5;", "a"), RuntimeValue::Number(5.0));
+    assert_eq!(get_variable("let x = 'a'; x = true;", "x"), RuntimeValue::Boolean(true));
+}
+
+#[test]
+fn test_compound_assignment() {
+    for (source, expected) in [
+        ("let x = 5; x += 2;", 7.0),
+        ("let x = 5; x -= 2;", 3.0),
+        ("let x = 5; x *= 2;", 10.0),
+        ("let x = 5; x /= 2;", 2.5),
+        ("let x = -5; x %= 3;", -2.0),
+        ("let x = 2; x **= 10;", 1024.0),
+        ("let x = 1; x <<= 31;", -2147483648.0),
+        ("let x = -16; x >>= 2;", -4.0),
+        ("let x = -1; x >>>= 28;", 15.0),
+        ("let x = 12; x &= 10;", 8.0),
+        ("let x = 12; x |= 3;", 15.0),
+        ("let x = 12; x ^= 10;", 6.0),
+        ("let x = 4294967297; x |= 0;", 1.0),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::Number(expected), "{}", source);
+    }
+
+    assert_eq!(get_variable("let x = 10n; x *= 3n;", "x"), bigint("30"));
+}
+
+#[test]
+fn test_string_concatenation() {
+    for (source, expected) in [
+        ("let x = 'a' + 1;", "a1"),
+        ("let x = 1 + 2 + '3';", "33"),
+        ("let x = '1' + 2 + 3;", "123"),
+        ("let x = 'n: ' + null + ' ' + undefined + ' ' + true;", "n: null undefined true"),
+        ("let x = 'big ' + 10n;", "big 10"),
+        ("let x = 0 / 0 + '';", "NaN"),
+        ("let x = Error('oops') + '!';", "Error: oops!"),
+        ("let x = 'a'; x += 1;", "a1"),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::String(expected.into()), "{}", source);
+    }
+}
+
+#[test]
+fn test_arithmetic_converts_to_numbers() {
+    for (source, expected) in [
+        ("let x = true + 1;", 2.0),
+        ("let x = null + 1;", 1.0),
+        ("let x = true + true;", 2.0),
+        ("let x = '6' * '7';", 42.0),
+        ("let x = '10' - 4;", 6.0),
+        ("let x = ' 0x10 ' / 2;", 8.0),
+        ("let x = '7' % 4;", 3.0),
+        ("let x = '2' ** 3;", 8.0),
+        ("let x = '12' & 10;", 8.0),
+        ("let x = true << 2;", 4.0),
+        ("let x = 1 + 1 == 2 & 1;", 1.0),
+        ("let x = '3'; x -= 1;", 2.0),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::Number(expected), "{}", source);
+    }
+
+    for source in ["let x = undefined + 1;", "let x = 'a' * 1;", "let x = Error() - 1;"] {
+        assert!(matches!(get_variable(source, "x"), RuntimeValue::Number(n) if n.is_nan()), "{}", source);
+    }
+
+    assert_eq!(get_error("let x = 1n + true;").message, "TypeError: Cannot mix BigInt and other types, use explicit conversions");
+    assert_eq!(get_error("let x = '1' * 1n;").code, "E0201");
+}
+
+#[test]
+fn test_number_to_string() {
+    for (value, expected) in [
+        (1.0, "1"),
+        (-0.0, "0"),
+        (-1.5, "-1.5"),
+        (123.456, "123.456"),
+        (0.1 + 0.2, "0.30000000000000004"),
+        (1.0 / 3.0, "0.3333333333333333"),
+        (1e20, "100000000000000000000"),
+        (123456789012345680000.0, "123456789012345680000"),
+        (1e21, "1e+21"),
+        (1.5e300, "1.5e+300"),
+        (0.000001, "0.000001"),
+        (0.0000012, "0.0000012"),
+        (1e-7, "1e-7"),
+        (1.5e-10, "1.5e-10"),
+        (5e-324, "5e-324"),
+        (f64::MAX, "1.7976931348623157e+308"),
+        (f64::NAN, "NaN"),
+        (f64::NEG_INFINITY, "-Infinity"),
+    ] {
+        assert_eq!(number::to_string(value), expected, "{:?}", value);
+    }
+
+    assert_eq!(get_variable("let x = `${1e21} ${0.1 * 3}`;", "x"), RuntimeValue::String("1e+21 0.30000000000000004".into()));
+}
+
+#[test]
+fn test_number_to_string_with_radix() {
+    for (source, expected) in [
+        ("let n = 255; let x = n.toString(16);", "ff"),
+        ("let x = (255).toString(2);", "11111111"),
+        ("let x = (-255).toString(36);", "-73"),
+        ("let x = (255.5).toString(16);", "ff.8"),
+        ("let x = (0.5).toString(2);", "0.1"),
+        ("let x = (0.1).toString(2);", "0.0001100110011001100110011001100110011001100110011001101"),
+        ("let x = (1e21).toString(16);", "3635c9adc5dea00000"),
+        ("let x = (-0).toString(2);", "0"),
+        ("let x = (0 / 0).toString(2);", "NaN"),
+        ("let x = (42).toString();", "42"),
+        ("let x = (42).toString(undefined);", "42"),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::String(expected.into()), "{}", source);
+    }
+
+    assert_eq!(get_error("(1).toString(1);").message, "RangeError: toString() radix must be between 2 and 36");
+    assert_eq!(get_error("(1).toString(37);").code, "E0205");
+}
+
+#[test]
+fn test_number_to_fixed() {
+    for (source, expected) in [
+        ("(123.456).toFixed(2)", "123.46"),
+        ("(1.005).toFixed(2)", "1.00"),
+        ("(1.255).toFixed(2)", "1.25"),
+        ("(0.5).toFixed(0)", "1"),
+        ("(2.5).toFixed(0)", "3"),
+        ("(-2.5).toFixed(0)", "-3"),
+        ("(1.5).toFixed()", "2"),
+        ("(0).toFixed(2)", "0.00"),
+        ("(-0).toFixed(2)", "0.00"),
+        ("(-1.5e-10).toFixed(2)", "-0.00"),
+        ("(0.000001).toFixed(7)", "0.0000010"),
+        ("(1.23e20).toFixed(2)", "123000000000000000000.00"),
+        ("(1e21).toFixed(2)", "1e+21"),
+        ("(1 / 0).toFixed(2)", "Infinity"),
+        ("(0.1).toFixed(20)", "0.10000000000000000555"),
+        ("(1.45).toFixed('1')", "1.4"),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::String(expected.into()), "{}", source);
+    }
+
+    assert_eq!(get_error("(1).toFixed(101);").message, "RangeError: toFixed() digits argument must be between 0 and 100");
+    assert_eq!(get_error("(1).toFixed(-1);").code, "E0205");
+}
+
+#[test]
+fn test_number_to_exponential_and_precision() {
+    for (source, expected) in [
+        ("(123456).toExponential(2)", "1.23e+5"),
+        ("(123.456).toExponential()", "1.23456e+2"),
+        ("(0.000123).toExponential(1)", "1.2e-4"),
+        ("(-5.5).toExponential(0)", "-6e+0"),
+        ("(0).toExponential()", "0e+0"),
+        ("(0).toExponential(2)", "0.00e+0"),
+        ("(1 / 0).toExponential(200)", "Infinity"),
+        ("(123.456).toPrecision(4)", "123.5"),
+        ("(99.99).toPrecision(3)", "100"),
+        ("(123456).toPrecision(2)", "1.2e+5"),
+        ("(0.000123).toPrecision(2)", "0.00012"),
+        ("(0.00000123).toPrecision(2)", "0.0000012"),
+        ("(1e-7).toPrecision(1)", "1e-7"),
+        ("(15).toPrecision(1)", "2e+1"),
+        ("(5).toPrecision(1)", "5"),
+        ("(0).toPrecision(3)", "0.00"),
+        ("(-1.5).toPrecision()", "-1.5"),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::String(expected.into()), "{}", source);
+    }
+
+    assert_eq!(get_error("(1).toExponential(-1);").message, "RangeError: toExponential() argument must be between 0 and 100");
+    assert_eq!(get_error("(1).toPrecision(0);").message, "RangeError: toPrecision() argument must be between 1 and 100");
+}
+
+#[test]
+fn test_method_calls_on_values_without_the_method() {
+    assert_eq!(get_error("let n = 1; n.toUpperCase();").message, "TypeError: n.toUpperCase is not a function");
+    assert_eq!(get_error("'a'.toFixed(1);").message, "TypeError: a.toFixed is not a function");
+    assert_eq!(get_error("null.toString();").message, "TypeError: Cannot read properties of null (reading 'toString')");
+    assert_eq!(get_error("(1)();").message, "TypeError: 1 is not a function");
+}
+
+#[test]
+fn test_string_to_number() {
+    for (source, expected) in [
+        ("'  0x1F ' * 1", 31.0),
+        ("Number('')", 0.0),
+        ("Number(' \\n\\t ')", 0.0),
+        ("Number('1e3')", 1000.0),
+        ("Number('-1.5E-2')", -0.015),
+        ("Number('.5')", 0.5),
+        ("Number('5.')", 5.0),
+        ("Number('1.e3')", 1000.0),
+        ("Number('+42')", 42.0),
+        ("Number('0b101')", 5.0),
+        ("Number('0o17')", 15.0),
+        ("Number('0x20000000000001')", 9007199254740992.0),
+        ("Number('9007199254740993')", 9007199254740992.0),
+        ("Number('\\u{A0}42\\u{FEFF}\\u{2028}')", 42.0),
+        ("Number('Infinity')", f64::INFINITY),
+        ("Number('-Infinity')", f64::NEG_INFINITY),
+        ("Number(true)", 1.0),
+        ("Number(null)", 0.0),
+        ("Number(12345678901234567890n)", 12345678901234567000.0),
+        ("Number()", 0.0),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::Number(expected), "{}", source);
+    }
+
+    for source in [
+        "Number('infinity')",
+        "Number('1_000')",
+        "Number('-0x10')",
+        "Number('0x')",
+        "Number('1e')",
+        "Number('.')",
+        "Number('1 2')",
+        "Number('\\u{85}1')",
+        "Number(undefined)",
+        "Number('12px')",
+    ] {
+        let source = format!("let x = {};", source);
+        assert!(matches!(get_variable(&source, "x"), RuntimeValue::Number(n) if n.is_nan()), "{}", source);
+    }
+}
+
+#[test]
+fn test_parse_int() {
+    for (source, expected) in [
+        ("parseInt('08', 10)", 8.0),
+        ("parseInt('08')", 8.0),
+        ("parseInt('0x1F')", 31.0),
+        ("parseInt('0x1F', 16)", 31.0),
+        ("parseInt('0x1F', 10)", 0.0),
+        ("parseInt('  -42px')", -42.0),
+        ("parseInt('+7.9')", 7.0),
+        ("parseInt('z', 36)", 35.0),
+        ("parseInt('10', 2)", 2.0),
+        ("parseInt('10', 0)", 10.0),
+        ("parseInt('123', 4294967312)", 291.0),
+        ("parseInt(0.0000005)", 5.0),
+        ("parseInt(1e21)", 1.0),
+        ("parseInt('9007199254740993')", 9007199254740992.0),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::Number(expected), "{}", source);
+    }
+
+    for source in ["parseInt('')", "parseInt('-')", "parseInt('0x')", "parseInt('10', 1)", "parseInt('10', 37)", "parseInt(null)", "parseInt('Infinity')"] {
+        let source = format!("let x = {};", source);
+        assert!(matches!(get_variable(&source, "x"), RuntimeValue::Number(n) if n.is_nan()), "{}", source);
+    }
+
+    assert!(matches!(get_variable("let x = parseInt('-0');", "x"), RuntimeValue::Number(n) if n == 0.0 && n.is_sign_negative()));
+}
+
+#[test]
+fn test_parse_float() {
+    for (source, expected) in [
+        ("parseFloat('2.5abc')", 2.5),
+        ("parseFloat('.5')", 0.5),
+        ("parseFloat('-.5e-3x')", -0.0005),
+        ("parseFloat('1e')", 1.0),
+        ("parseFloat('1e+')", 1.0),
+        ("parseFloat('1.e5')", 100000.0),
+        ("parseFloat('1_000')", 1.0),
+        ("parseFloat('0x10')", 0.0),
+        ("parseFloat('  \\n 42')", 42.0),
+        ("parseFloat('Infinityx')", f64::INFINITY),
+        ("parseFloat('-Infinity')", f64::NEG_INFINITY),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::Number(expected), "{}", source);
+    }
+
+    for source in ["parseFloat('')", "parseFloat('.')", "parseFloat('-')", "parseFloat('abc')", "parseFloat(Error('1'))"] {
+        let source = format!("let x = {};", source);
+        assert!(matches!(get_variable(&source, "x"), RuntimeValue::Number(n) if n.is_nan()), "{}", source);
+    }
+
+    assert!(matches!(get_variable("let x = parseFloat('-0');", "x"), RuntimeValue::Number(n) if n == 0.0 && n.is_sign_negative()));
+}
+
+#[test]
+fn test_assignment_to_const_is_type_error() {
+    let (runtime, errors) = run("const x = 1; x = 2;");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, "E0201");
+    assert_eq!(errors[0].message, "TypeError: Assignment to constant variable.");
+    assert_eq!(runtime.environment.get("x"), Some(RuntimeValue::Number(1.0)));
+
+    assert_eq!(get_error("const x = 1; x += 1;").code, "E0201");
+}
+
+#[test]
+fn test_assignment_to_undeclared_variable_is_reference_error() {
+    assert_eq!(get_error("y = 1;").message, "ReferenceError: y is not defined");
+    assert_eq!(get_error("y += 1;").message, "ReferenceError: y is not defined");
+}
+
+#[test]
+fn test_reading_undeclared_variable_is_reference_error() {
+    assert_eq!(get_error("let x = y + 1;").message, "ReferenceError: y is not defined");
+    assert_eq!(get_error("{ let y = 1; } let x = y;").message, "ReferenceError: y is not defined");
+    assert_eq!(get_error("function f() { return y; } f();").code, "E0207");
+}
+
+#[test]
+fn test_global_value_properties() {
+    assert_eq!(get_variable("let x = 1 + Infinity;", "x"), RuntimeValue::Number(f64::INFINITY));
+    assert_eq!(get_variable("let x = -Infinity < 0;", "x"), RuntimeValue::Boolean(true));
+    assert_eq!(get_variable("let x = NaN !== NaN;", "x"), RuntimeValue::Boolean(true));
+    assert_eq!(get_variable("let x = typeof NaN;", "x"), RuntimeValue::String("number".into()));
+    assert_eq!(get_error("NaN = 1;").message, "TypeError: Assignment to constant variable.");
+    assert_eq!(get_variable("{ let NaN = 1; NaN = 2; } let x = NaN !== NaN;", "x"), RuntimeValue::Boolean(true));
+}
+
+#[test]
+fn test_constants_are_not_replaced_by_declarations() {
+    let mut environment = environment::Environment::new();
+    assert!(environment.declare("c".into(), RuntimeValue::Number(1.0), true));
+    assert!(!environment.declare("c".into(), RuntimeValue::Number(2.0), false));
+    assert_eq!(environment.get("c"), Some(RuntimeValue::Number(1.0)));
+    assert!(environment.is_constant("c"));
+
+    let mut runtime = Runtime::new();
+    assert!(!runtime.environment.declare("NaN".into(), RuntimeValue::Number(5.0), false));
+    assert!(runtime.environment.is_constant("NaN"));
+}
+
+#[test]
+fn test_if_else() {
+    let source = "let x = 0; if (1 > 2) { x = 1; } else if ('') { x = 2; } else { x = 3; }";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::Number(3.0));
+    assert_eq!(get_variable("let x = 0; if (0n) x = 1; else x = 2;", "x"), RuntimeValue::Number(2.0));
+    assert_eq!(get_variable("let x = 0; if ('a') x = 1;", "x"), RuntimeValue::Number(1.0));
+    assert_eq!(get_variable("let x = 0; if (0 / 0) x = 1;", "x"), RuntimeValue::Number(0.0));
+}
+
+#[test]
+fn test_block_scoped_declarations_do_not_leak() {
+    let (runtime, errors) = run("let a = 1; { let b = 2; const c = 3; a = b + c; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("a"), Some(RuntimeValue::Number(5.0)));
+    assert_eq!(runtime.environment.get("b"), None);
+    assert_eq!(runtime.environment.get("c"), None);
+}
+
+#[test]
+fn test_block_declarations_shadow_outer_ones() {
+    let source = "
+        const x = 'outer';
+        let seen;
+        {
+            let x = 'inner';
+            x = 'changed';
+            { const x = 'innermost'; seen = x; }
+        }
+    ";
+    let (runtime, errors) = run(source);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("x"), Some(RuntimeValue::String("outer".into())));
+    assert_eq!(runtime.environment.get("seen"), Some(RuntimeValue::String("innermost".into())));
+
+    // the outer `x` is a const again once the block is left
+    assert_eq!(get_error("const x = 1; { let x = 2; } x = 3;").code, "E0201");
+}
+
+#[test]
+fn test_block_is_left_after_an_error() {
+    let (runtime, errors) = run("try { let a = 1; undeclared = 2; } catch {} let a = 3;");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("a"), Some(RuntimeValue::Number(3.0)));
+}
+
+#[test]
+fn test_return_from_nested_statements() {
+    let source = "
+        function sign(n) {
+            if (n > 0) { return 1; }
+            else if (n < 0) { { return -1; } }
+            return 0;
+        }
+        let a = sign(5); let b = sign(-5); let c = sign(0);
+    ";
+    let (runtime, errors) = run(source);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("a"), Some(RuntimeValue::Number(1.0)));
+    assert_eq!(runtime.environment.get("b"), Some(RuntimeValue::Number(-1.0)));
+    assert_eq!(runtime.environment.get("c"), Some(RuntimeValue::Number(0.0)));
+}
+
+#[test]
+fn test_while_loop() {
+    let source = "let i = 0; let sum = 0; while (i < 5) { i += 1; if (i == 2) continue; if (i == 4) break; sum += i; }";
+    assert_eq!(get_variable(source, "sum"), RuntimeValue::Number(4.0));
+    assert_eq!(get_variable("let i = 0; while (false) i = 1;", "i"), RuntimeValue::Number(0.0));
+}
+
+#[test]
+fn test_do_while_loop_runs_at_least_once() {
+    assert_eq!(get_variable("let i = 0; do i += 1; while (false)", "i"), RuntimeValue::Number(1.0));
+    assert_eq!(
+        get_variable("let i = 0; do { i += 1; if (i < 3) continue; } while (i < 5)", "i"),
+        RuntimeValue::Number(5.0)
+    );
+}
+
+#[test]
+fn test_for_loop() {
+    let (runtime, errors) = run("let sum = 0; for (let i = 0; i < 4; i += 1) { let square = i * i; sum += square; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("sum"), Some(RuntimeValue::Number(14.0)));
+    assert_eq!(runtime.environment.get("i"), None);
+    assert_eq!(runtime.environment.get("square"), None);
+
+    assert_eq!(get_variable("let i = 0; for (i = 10; i < 13; i += 1) {}", "i"), RuntimeValue::Number(13.0));
+    assert_eq!(get_variable("let n = 0; for (;;) { n += 1; if (n == 3) break; }", "n"), RuntimeValue::Number(3.0));
+}
+
+#[test]
+fn test_for_loop_const_in_head() {
+    assert_eq!(get_variable("let n = 0; for (const c = 2; n < c;) n += 1;", "n"), RuntimeValue::Number(2.0));
+    assert_eq!(get_error("for (const c = 0; c < 2; c += 1) {}").code, "E0201");
+}
+
+#[test]
+fn test_labeled_break_and_continue() {
+    let source = "
+        let pairs = '';
+        outer: for (let i = 0; i < 3; i += 1) {
+            for (let j = 0; j < 3; j += 1) {
+                if (j == 1) continue outer;
+                if (i == 2) break outer;
+                pairs = `${pairs}${i}${j} `;
+            }
+        }
+    ";
+    assert_eq!(get_variable(source, "pairs"), RuntimeValue::String("00 10 ".into()));
+
+    assert_eq!(get_variable("let x = 0; block: { x = 1; break block; x = 2; }", "x"), RuntimeValue::Number(1.0));
+}
+
+#[test]
+fn test_return_from_loop() {
+    let source = "
+        function first_multiple(n, of) {
+            for (let i = 1; true; i += 1) {
+                if (i * n > of) { return i * n; }
+            }
+        }
+        let x = first_multiple(7, 30);
+    ";
+    assert_eq!(get_variable(source, "x"), RuntimeValue::Number(35.0));
+}
+
+#[test]
+fn test_loop_scopes_are_left_after_an_error() {
+    let (runtime, errors) = run("for (let i = 0; i < 3; i += 1) { undeclared = i; }");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(runtime.environment.get("i"), None);
+}
+
+#[test]
+fn test_for_of_string() {
+    let source = "let reversed = ''; for (const c of 'h\u{e9}\u{1f600}') { reversed = `${c}${reversed}`; }";
+    assert_eq!(get_variable(source, "reversed"), RuntimeValue::String("\u{1f600}\u{e9}h".into()));
+
+    let source = "let last = ''; for (last of 'abc') { if (last == 'b') break; }";
+    assert_eq!(get_variable(source, "last"), RuntimeValue::String("b".into()));
+}
+
+#[test]
+fn test_for_of_not_iterable() {
+    let error = get_error("for (const x of 5) {}");
+    assert_eq!(error.code, "E0201");
+    assert_eq!(error.message, "TypeError: 5 is not iterable");
+}
+
+#[test]
+fn test_for_in_string() {
+    // the indices are those of the UTF-16 code units
+    let (runtime, errors) = run("let keys = ''; for (let key in 'a\u{1f600}') { keys = `${keys}${key},`; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("keys"), Some(RuntimeValue::String("0,1,2,".into())));
+    assert_eq!(runtime.environment.get("key"), None);
+
+    assert_eq!(get_variable("let n = 0; for (const k in 42) n += 1; for (const k in null) n += 1;", "n"), RuntimeValue::Number(0.0));
+}
+
+#[test]
+fn test_switch_falls_through_until_break() {
+    let source = "
+        function describe(x) {
+            let description = '';
+            switch (x) {
+                case 1:
+                    description = `${description}one `;
+                case 2:
+                    description = `${description}two `;
+                    break;
+                default:
+                    description = `${description}default `;
+                case 3:
+                    description = `${description}three `;
+            }
+            return description;
+        }
+        let a = describe(1);
+        let b = describe(2);
+        let c = describe(3);
+        let d = describe(4);
+    ";
+    assert_eq!(get_variable(source, "a"), RuntimeValue::String("one two ".into()));
+    assert_eq!(get_variable(source, "b"), RuntimeValue::String("two ".into()));
+    assert_eq!(get_variable(source, "c"), RuntimeValue::String("three ".into()));
+    assert_eq!(get_variable(source, "d"), RuntimeValue::String("default three ".into()));
+}
+
+#[test]
+fn test_switch_uses_strict_equality() {
+    assert_eq!(get_variable("let r = 0; switch ('1') { case 1: r = 1; break; case '1': r = 2; }", "r"), RuntimeValue::Number(2.0));
+    assert_eq!(get_variable("let r = 0; switch (0 / 0) { case 0 / 0: r = 1; }", "r"), RuntimeValue::Number(0.0));
+    assert_eq!(get_variable("let r = 0; switch (-0) { case 0: r = 1; }", "r"), RuntimeValue::Number(1.0));
+}
+
+#[test]
+fn test_switch_shares_one_scope() {
+    let (runtime, errors) = run("let r = 0; switch (1) { case 1: let x = 1; case 2: x += 1; r = x; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("r"), Some(RuntimeValue::Number(2.0)));
+    assert_eq!(runtime.environment.get("x"), None);
+}
+
+#[test]
+fn test_break_and_continue_around_switch() {
+    let source = "
+        let n = 0;
+        outer: for (let i = 0; i < 5; i += 1) {
+            switch (i) {
+                case 1: continue;
+                case 3: break outer;
+                default: n += 1;
+            }
+            n += 10;
+        }
+    ";
+    assert_eq!(get_variable(source, "n"), RuntimeValue::Number(22.0));
+}
+
+#[test]
+fn test_uncaught_exception_stops_execution() {
+    let (runtime, errors) = run("let a = 1; throw 'oops'; a = 2;");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, "E0208");
+    assert_eq!(errors[0].message, "Uncaught oops");
+    assert_eq!(runtime.environment.get("a"), Some(RuntimeValue::Number(1.0)));
+
+    let (runtime, errors) = run("let a = 1; undeclared = 3; a = 2;");
+    assert_eq!(errors[0].code, "E0207");
+    assert_eq!(runtime.environment.get("a"), Some(RuntimeValue::Number(1.0)));
+}
+
+#[test]
+fn test_catch_thrown_values() {
+    assert_eq!(get_variable("let c = 0; try { throw 42; c = 1; } catch (e) { c = e; }", "c"), RuntimeValue::Number(42.0));
+
+    let source = "
+        function fail(n) { if (n > 2) { throw `too big: ${n}`; } return n; }
+        let c = '';
+        try { fail(1); fail(3); } catch (e) { c = e; }
+    ";
+    assert_eq!(get_variable(source, "c"), RuntimeValue::String("too big: 3".into()));
+
+    let (runtime, errors) = run("let c = 0; try { throw 1; } catch { c = 2; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("c"), Some(RuntimeValue::Number(2.0)));
+}
+
+#[test]
+fn test_runtime_errors_are_catchable_error_objects() {
+    let source = "
+        let name = ''; let message = ''; let text = '';
+        try { const x = 1; x = 2; } catch (e) { name = e.name; message = e.message; text = `${e}`; }
+    ";
+    assert_eq!(get_variable(source, "name"), RuntimeValue::String("TypeError".into()));
+    assert_eq!(get_variable(source, "message"), RuntimeValue::String("Assignment to constant variable.".into()));
+    assert_eq!(get_variable(source, "text"), RuntimeValue::String("TypeError: Assignment to constant variable.".into()));
+
+    for (source, expected) in [
+        ("undeclared = 1;", "ReferenceError"),
+        ("nope;", "ReferenceError"),
+        ("{ let x = 1; } x;", "ReferenceError"),
+        ("1n / 0n;", "RangeError"),
+        ("BigInt('1.5');", "SyntaxError"),
+        ("missing();", "ReferenceError"),
+        ("let x = 1; x();", "TypeError"),
+    ] {
+        let source = format!("let name = ''; try {{ {} }} catch (e) {{ name = e.name; }}", source);
+        assert_eq!(get_variable(&source, "name"), RuntimeValue::String(expected.into()), "{}", source);
+    }
+}
+
+#[test]
+fn test_internal_errors_are_not_catchable() {
+    // `1 = 2` can't be parsed, the runtime only sees it if the parser has a bug
+    let span = Span::default();
+    let literal = |value| Box::new(Expression::Literal { value: Literal::Number(value), span });
+    let assignment = Expression::Assignment { target: literal(1.0), operator: TokenType::Assign, value: literal(2.0), span };
+    let block = Statement::Block {
+        body: vec![Statement::ExpressionStatement { expression: Box::new(assignment), span }],
+        span,
+    };
+    let handler = CatchClause { param: None, body: Box::new(Statement::Block { body: vec![], span }), span };
+    let program = vec![Statement::Try { block: Box::new(block), handler: Some(handler), finalizer: None, span }];
+
+    let error = Runtime::new().interpret(program).expect_err("the internal error should not be caught");
+    assert_eq!(error.code, "E0209");
+    assert_eq!(error.message, "internal error: invalid assignment target");
+}
+
+#[test]
+fn test_calling_a_variable_is_type_error() {
+    assert_eq!(get_error("let x = 1; x();").message, "TypeError: x is not a function");
+    assert_eq!(get_error("let BigInt = 1; BigInt(2);").message, "TypeError: BigInt is not a function");
+    assert_eq!(get_error("function f() { let g = 'g'; g(); } f();").code, "E0201");
+    assert_eq!(get_error("missing();").code, "E0202");
+}
+
+#[test]
+fn test_error_constructors() {
+    let source = "let e = RangeError('out of range'); let name = e.name; let stack = e.stack; let missing = e.missing;";
+    assert_eq!(get_variable(source, "name"), RuntimeValue::String("RangeError".into()));
+    assert_eq!(get_variable(source, "stack"), RuntimeValue::String("RangeError: out of range\n    at 1:9".into()));
+    assert_eq!(get_variable(source, "missing"), RuntimeValue::Undefined);
+    assert_eq!(get_variable("let s = `${Error()}`;", "s"), RuntimeValue::String("Error".into()));
+
+    let error = get_error("throw TypeError('bad');");
+    assert_eq!(error.code, "E0208");
+    assert_eq!(error.message, "Uncaught TypeError: bad");
+
+    assert_eq!(get_error("let u; u.message;").message, "TypeError: Cannot read properties of undefined (reading 'message')");
+}
+
+#[test]
+fn test_rethrown_error_is_the_same_object() {
+    let source = "
+        let original; let same = false;
+        try {
+            try { original = Error('x'); throw original; } catch (e) { throw e; }
+        } catch (e) { switch (e) { case original: same = true; } }
+    ";
+    assert_eq!(get_variable(source, "same"), RuntimeValue::Boolean(true));
+}
+
+#[test]
+fn test_finally() {
+    let source = "let log = ''; while (true) { try { log = `${log}try `; break; } finally { log = `${log}finally`; } }";
+    assert_eq!(get_variable(source, "log"), RuntimeValue::String("try finally".into()));
+
+    let source = "function f() { let n = 1; try { return n; } finally { n = 2; } } let result = f();";
+    assert_eq!(get_variable(source, "result"), RuntimeValue::Number(1.0));
+
+    let source = "function f() { try { throw 1; } finally { return 2; } } let result = f();";
+    assert_eq!(get_variable(source, "result"), RuntimeValue::Number(2.0));
+
+    let source = "let n = 0; for (let i = 0; i < 3; i += 1) { try { continue; } finally { n += 1; } }";
+    assert_eq!(get_variable(source, "n"), RuntimeValue::Number(3.0));
+
+    let (runtime, errors) = run("let n = 0; try { throw 'x'; } finally { n = 1; }");
+    assert_eq!(errors[0].message, "Uncaught x");
+    assert_eq!(runtime.environment.get("n"), Some(RuntimeValue::Number(1.0)));
+}
+
+#[test]
+fn test_catch_binding_is_scoped() {
+    let (runtime, errors) = run("let e = 'outer'; try { throw 'inner'; } catch (e) { e = 'changed'; }");
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(runtime.environment.get("e"), Some(RuntimeValue::String("outer".into())));
+}
+
+#[test]
+fn test_logical_operators_return_operands() {
+    for (source, expected) in [
+        ("let x = 0 || 'fallback';", RuntimeValue::String("fallback".into())),
+        ("let x = 'first' || 'second';", RuntimeValue::String("first".into())),
+        ("let x = 1 && 'second';", RuntimeValue::String("second".into())),
+        ("let x = '' && 'second';", RuntimeValue::String("".into())),
+        ("let x = null ?? 0;", RuntimeValue::Number(0.0)),
+        ("let x = 0 ?? 1;", RuntimeValue::Number(0.0)),
+        ("let x = undefined ?? null;", RuntimeValue::Null),
+        ("let x = !0;", RuntimeValue::Boolean(true)),
+        ("let x = !!'text';", RuntimeValue::Boolean(true)),
+        ("let x = !0n;", RuntimeValue::Boolean(true)),
+    ] {
+        assert_eq!(get_variable(source, "x"), expected, "{}", source);
+    }
+}
+
+#[test]
+fn test_logical_operators_short_circuit() {
+    assert_eq!(get_variable("let x = 0; false && (x = 1); true || (x = 2); 0 ?? (x = 3);", "x"), RuntimeValue::Number(0.0));
+    assert_eq!(get_variable("let x = 0; true && (x = 1);", "x"), RuntimeValue::Number(1.0));
+    // the right operand isn't evaluated, so it can't throw
+    assert_eq!(get_variable("let x = 1 || undeclared();", "x"), RuntimeValue::Number(1.0));
+}
+
+#[test]
+fn test_logical_assignment() {
+    for (source, expected) in [
+        ("let x = 1; x &&= 2;", RuntimeValue::Number(2.0)),
+        ("let x = 0; x &&= 2;", RuntimeValue::Number(0.0)),
+        ("let x = 0; x ||= 2;", RuntimeValue::Number(2.0)),
+        ("let x = 1; x ||= 2;", RuntimeValue::Number(1.0)),
+        ("let x = null; x ??= 2;", RuntimeValue::Number(2.0)),
+        ("let x = false; x ??= 2;", RuntimeValue::Boolean(false)),
+    ] {
+        assert_eq!(get_variable(source, "x"), expected, "{}", source);
+    }
+
+    // a short-circuiting logical assignment doesn't assign, even to a constant
+    assert_eq!(get_variable("const x = 1; let y = x ||= 2;", "y"), RuntimeValue::Number(1.0));
+    assert_eq!(get_error("const x = 0; x ||= 2;").message, "TypeError: Assignment to constant variable.");
+}
+
+#[test]
+fn test_conditional_operator() {
+    let source = "
+        function sign(n) { return n > 0 ? 'positive' : n < 0 ? 'negative' : 'zero'; }
+        let a = sign(5); let b = sign(-2); let c = sign(0);
+    ";
+    assert_eq!(get_variable(source, "a"), RuntimeValue::String("positive".into()));
+    assert_eq!(get_variable(source, "b"), RuntimeValue::String("negative".into()));
+    assert_eq!(get_variable(source, "c"), RuntimeValue::String("zero".into()));
+
+    // only the chosen branch is evaluated
+    assert_eq!(get_variable("let x = 0; let y = '' ? (x = 1) : (x = 2);", "x"), RuntimeValue::Number(2.0));
+}
+
+#[test]
+fn test_comma_operator() {
+    assert_eq!(get_variable("let a = 0; let x = (a = 1, a + 1, a * 10);", "x"), RuntimeValue::Number(10.0));
+    assert_eq!(
+        get_variable("let n = 0; let j = 10; for (let i = 0; i < j; i += 1, j -= 1) n += 1;", "n"),
+        RuntimeValue::Number(5.0)
+    );
+}
+
+#[test]
+fn test_loose_equality() {
+    for (source, expected) in [
+        ("1 == '1'", true),
+        ("0 == ''", true),
+        ("'0x10' == 16", true),
+        ("null == undefined", true),
+        ("null == 0", false),
+        ("undefined == false", false),
+        ("true == 1", true),
+        ("true == '1'", true),
+        ("false == '0'", true),
+        ("0 / 0 == 0 / 0", false),
+        ("0 == -0", true),
+        ("1n == '1'", true),
+        ("1n == '1.5'", false),
+        ("2n == true", false),
+        ("Error('x') == 'Error: x'", true),
+        ("Error('x') == Error('x')", false),
+        ("'b' != 'a'", true),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::Boolean(expected), "{}", source);
+    }
+
+    assert_eq!(get_variable("let e = Error(); let x = e == e;", "x"), RuntimeValue::Boolean(true));
+}
+
+#[test]
+fn test_strict_equality() {
+    for (source, expected) in [
+        ("null === null", true),
+        ("null !== null", false),
+        ("undefined === undefined", true),
+        ("null === undefined", false),
+        ("1 === '1'", false),
+        ("1 !== '1'", true),
+        ("0 === -0", true),
+        ("0 / 0 === 0 / 0", false),
+        ("0 / 0 !== 0 / 0", true),
+        ("1n === 1n", true),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::Boolean(expected), "{}", source);
+    }
+}
+
+#[test]
+fn test_relational_comparison() {
+    for (source, expected) in [
+        ("'10' < 9", false),
+        ("'10' < '9'", true),
+        ("'a' < 'b'", true),
+        ("'ab' < 'a'", false),
+        ("'\u{FF61}' < '\u{1F600}'", false),
+        ("null < 1", true),
+        ("null >= 0", true),
+        ("null <= 0", true),
+        ("undefined < 1", false),
+        ("undefined >= 1", false),
+        ("0 / 0 <= 0 / 0", false),
+        ("-0 < 0", false),
+        ("-0 <= 0", true),
+        ("true > false", true),
+        ("1n < '2'", true),
+        ("'10' > 9n", true),
+        ("1n < 'x'", false),
+        ("1n >= 'x'", false),
+        ("2n > 1.5", true),
+        ("Error('b') > 'Error: a'", true),
+    ] {
+        let source = format!("let x = {};", source);
+        assert_eq!(get_variable(&source, "x"), RuntimeValue::Boolean(expected), "{}", source);
+    }
+}
+
+#[test]
+fn test_comparison_in_parentheses() {
+    assert_eq!(get_variable("let x = (1 == 1);", "x"), RuntimeValue::Boolean(true));
+}
+
+#[test]
+fn test_unary_operators() {
+    for (source, expected) in [
+        ("let x = +'42';", RuntimeValue::Number(42.0)),
+        ("let x = +' 0x1F ';", RuntimeValue::Number(31.0)),
+        ("let x = +'';", RuntimeValue::Number(0.0)),
+        ("let x = +true;", RuntimeValue::Number(1.0)),
+        ("let x = +null;", RuntimeValue::Number(0.0)),
+        ("let x = -'-2.5e1';", RuntimeValue::Number(25.0)),
+        ("let x = +'-Infinity';", RuntimeValue::Number(f64::NEG_INFINITY)),
+        ("let x = ~5;", RuntimeValue::Number(-6.0)),
+        ("let x = ~'-1';", RuntimeValue::Number(0.0)),
+        ("let x = ~4294967296;", RuntimeValue::Number(-1.0)),
+        ("let x = ~5n;", bigint("-6")),
+        ("let x = -5n;", bigint("-5")),
+        ("let x = void 1;", RuntimeValue::Undefined),
+    ] {
+        assert_eq!(get_variable(source, "x"), expected, "{}", source);
+    }
+
+    for source in ["let x = +undefined;", "let x = +'12px';", "let x = +'inf';", "let x = +'1_000';", "let x = +Error();"] {
+        assert!(matches!(get_variable(source, "x"), RuntimeValue::Number(n) if n.is_nan()), "{}", source);
+    }
+
+    assert_eq!(get_error("+1n;").message, "TypeError: Cannot convert a BigInt value to a number");
+}
+
+#[test]
+fn test_typeof() {
+    for (source, expected) in [
+        ("let x = typeof 1;", "number"),
+        ("let x = typeof 1n;", "bigint"),
+        ("let x = typeof '';", "string"),
+        ("let x = typeof true;", "boolean"),
+        ("let x = typeof null;", "object"),
+        ("let x = typeof undefined;", "undefined"),
+        ("let x = typeof undeclared;", "undefined"),
+        ("let x = typeof TypeError('');", "object"),
+        ("function f() {} let x = typeof f;", "function"),
+        ("let x = typeof BigInt;", "function"),
+        ("let x = typeof typeof 1;", "string"),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::String(expected.into()), "{}", source);
+    }
+
+    // only `typeof` can look at an undeclared variable without throwing
+    assert_eq!(get_variable("let x = typeof undeclared === 'undefined';", "x"), RuntimeValue::Boolean(true));
+    assert_eq!(get_variable("let x = typeof ((undeclared));", "x"), RuntimeValue::String("undefined".into()));
+    assert_eq!(get_error("let x = undeclared;").message, "ReferenceError: undeclared is not defined");
+    assert_eq!(get_error("let x = typeof (undeclared + 1);").code, "E0207");
+}
+
+#[test]
+fn test_delete() {
+    for (source, expected) in [
+        ("let a = 1; let x = delete a;", false),
+        ("let x = delete undeclared;", true),
+        ("let x = delete 1;", true),
+        ("let e = Error('m'); let x = delete e.message;", false),
+        ("let e = Error('m'); let x = delete e.name;", true),
+        ("let x = delete 'text'.length;", true),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::Boolean(expected), "{}", source);
+    }
+
+    assert_eq!(get_error("let u; delete u.x;").message, "TypeError: Cannot convert undefined or null to object");
+}
+
+#[test]
+fn test_remainder_and_exponent() {
+    for (source, expected) in [
+        ("let x = 7 % 3;", 1.0),
+        ("let x = -5 % 3;", -2.0),
+        ("let x = 5.5 % 2;", 1.5),
+        ("let x = 2 ** 3 ** 2;", 512.0),
+        ("let x = (-2) ** 2;", 4.0),
+        ("let x = 2 ** -1;", 0.5),
+        ("let x = 1 + 2 * 3 ** 2 % 5;", 4.0),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::Number(expected), "{}", source);
+    }
+
+    assert!(matches!(get_variable("let x = 1 % 0;", "x"), RuntimeValue::Number(n) if n.is_nan()));
+}
+
+#[test]
+fn test_bitwise_and_shift_operators() {
+    for (source, expected) in [
+        ("let x = 6 & 3;", 2.0),
+        ("let x = 6 | 3;", 7.0),
+        ("let x = 6 ^ 3;", 5.0),
+        ("let x = 1 << 32;", 1.0),
+        ("let x = 1 << 31;", -2147483648.0),
+        ("let x = -16 >> 2;", -4.0),
+        ("let x = -1 >>> 0;", 4294967295.0),
+        ("let x = -1 >>> 28;", 15.0),
+        ("let x = 4294967297 | 0;", 1.0),
+        ("let x = 2.9 | 0;", 2.0),
+        ("let x = 1 | 2 ^ 3 & 4 << 1;", 3.0),
+    ] {
+        assert_eq!(get_variable(source, "x"), RuntimeValue::Number(expected), "{}", source);
+    }
+
+    assert_eq!(get_variable("let x = 8; x >>>= 1; x <<= 2; x ^= 1;", "x"), RuntimeValue::Number(17.0));
+}
+
+#[test]
+fn test_update_operators() {
+    let source = "let a = 1; let b = a++; let c = ++a; let d = a--; let e = --a;";
+    assert_eq!(get_variable(source, "a"), RuntimeValue::Number(1.0));
+    assert_eq!(get_variable(source, "b"), RuntimeValue::Number(1.0));
+    assert_eq!(get_variable(source, "c"), RuntimeValue::Number(3.0));
+    assert_eq!(get_variable(source, "d"), RuntimeValue::Number(3.0));
+    assert_eq!(get_variable(source, "e"), RuntimeValue::Number(1.0));
+
+    // the old value is converted to a number too
+    assert_eq!(get_variable("let a = '5'; let b = a++;", "b"), RuntimeValue::Number(5.0));
+    assert_eq!(get_variable("let a = 9007199254740993n; a++;", "a"), bigint("9007199254740994"));
+    assert_eq!(get_variable("let n = 0; for (let i = 0; i < 4; i++) n += i;", "n"), RuntimeValue::Number(6.0));
+
+    assert_eq!(get_error("const a = 1; a++;").message, "TypeError: Assignment to constant variable.");
+    assert_eq!(get_error("undeclared++;").code, "E0207");
+}
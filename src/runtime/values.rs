@@ -1,12 +1,54 @@
-use crate::common::Literal;
+use std::{cmp::Ordering, fmt, rc::Rc};
+use super::conversions::{string_to_bigint, string_to_number, to_numeric, to_primitive, to_string};
+use crate::common::{BigInt, Literal, Span};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RuntimeValue {
     Number(f64),
+    BigInt(BigInt),
     String(String),
     Boolean(bool),
     Null,
     Undefined,
+    // the only objects so far, shared so that a caught error is the one that was thrown
+    Error(Rc<ErrorObject>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ErrorObject {
+    // `Error`, `TypeError`, `RangeError`, `ReferenceError` or `SyntaxError`
+    pub name: String,
+    pub message: String,
+    pub stack: String,
+}
+
+impl ErrorObject {
+    pub fn new(name: impl Into<String>, message: impl Into<String>, span: Span) -> Self {
+        let mut error = Self { name: name.into(), message: message.into(), stack: String::new() };
+        error.stack = format!("{}\n    at {}:{}", error, span.line, span.column);
+        error
+    }
+
+    /// The property named `name`, `undefined` when there is none.
+    pub fn get(&self, name: &str) -> RuntimeValue {
+        match name {
+            "name" => RuntimeValue::String(self.name.clone()),
+            "message" => RuntimeValue::String(self.message.clone()),
+            "stack" => RuntimeValue::String(self.stack.clone()),
+            _ => RuntimeValue::Undefined,
+        }
+    }
+}
+
+/// Error.prototype.toString, the name alone when there is no message.
+impl fmt::Display for ErrorObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.message.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{}: {}", self.name, self.message)
+        }
+    }
 }
 
 impl From<Literal> for RuntimeValue {
@@ -15,8 +57,93 @@ impl From<Literal> for RuntimeValue {
             Literal::Boolean(value) => RuntimeValue::Boolean(value),
             Literal::Null => RuntimeValue::Null,
             Literal::Number(value) => RuntimeValue::Number(value),
+            Literal::BigInt(value) => RuntimeValue::BigInt(value),
             Literal::String(value) => RuntimeValue::String(value),
             Literal::Undefined => RuntimeValue::Undefined,
         }
     }
-}
\ No newline at end of file
+}
+impl RuntimeValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            RuntimeValue::Number(_) => "number",
+            RuntimeValue::BigInt(_) => "bigint",
+            RuntimeValue::String(_) => "string",
+            RuntimeValue::Boolean(_) => "boolean",
+            RuntimeValue::Null => "null",
+            RuntimeValue::Undefined => "undefined",
+            RuntimeValue::Error(_) => "object",
+        }
+    }
+
+    /// What `typeof` results in for a value.
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            RuntimeValue::Null => "object",
+            value => value.type_name(),
+        }
+    }
+
+    /// IsStrictlyEqual, what `===` and `switch` compare with. Objects are equal
+    /// when they are the same, for primitives it's the derived equality: `NaN`
+    /// isn't equal to itself and `0` equals `-0`.
+    pub fn is_strictly_equal(&self, other: &RuntimeValue) -> bool {
+        match (self, other) {
+            (RuntimeValue::Error(a), RuntimeValue::Error(b)) => Rc::ptr_eq(a, b),
+            _ => self == other,
+        }
+    }
+
+    /// IsLooselyEqual, what `==` compares with. `null` and `undefined` only equal
+    /// each other, anything else is converted until both sides have the same type.
+    pub fn is_loosely_equal(&self, other: &RuntimeValue) -> bool {
+        match (self, other) {
+            (a, b) if std::mem::discriminant(a) == std::mem::discriminant(b) => a.is_strictly_equal(b),
+            (RuntimeValue::Null | RuntimeValue::Undefined, RuntimeValue::Null | RuntimeValue::Undefined) => true,
+            (RuntimeValue::Number(n), RuntimeValue::String(s)) | (RuntimeValue::String(s), RuntimeValue::Number(n)) => {
+                *n == string_to_number(s)
+            }
+            (RuntimeValue::BigInt(n), RuntimeValue::String(s)) | (RuntimeValue::String(s), RuntimeValue::BigInt(n)) => {
+                string_to_bigint(s).is_some_and(|s| *n == s)
+            }
+            (RuntimeValue::Boolean(b), other) | (other, RuntimeValue::Boolean(b)) => {
+                RuntimeValue::Number(*b as u8 as f64).is_loosely_equal(other)
+            }
+            // objects are only compared with primitives, never with null or undefined
+            (RuntimeValue::Error(_), RuntimeValue::Number(_) | RuntimeValue::BigInt(_) | RuntimeValue::String(_))
+            | (RuntimeValue::Number(_) | RuntimeValue::BigInt(_) | RuntimeValue::String(_), RuntimeValue::Error(_)) => {
+                to_primitive(self).is_loosely_equal(&to_primitive(other))
+            }
+            (RuntimeValue::BigInt(a), RuntimeValue::Number(b)) | (RuntimeValue::Number(b), RuntimeValue::BigInt(a)) => {
+                a.partial_cmp_f64(*b) == Some(Ordering::Equal)
+            }
+            _ => false,
+        }
+    }
+
+    /// IsLessThan, `None` when the values can't be ordered: a `NaN`, or a string
+    /// that isn't an integer compared with a BigInt. Strings compare by UTF-16 code units.
+    pub fn is_less_than(&self, other: &RuntimeValue) -> Option<bool> {
+        let (x, y) = (to_primitive(self), to_primitive(other));
+        let ordering = match (&x, &y) {
+            (RuntimeValue::String(a), RuntimeValue::String(b)) => return Some(a.encode_utf16().lt(b.encode_utf16())),
+            (RuntimeValue::BigInt(a), RuntimeValue::String(b)) => a.partial_cmp(&string_to_bigint(b)?),
+            (RuntimeValue::String(a), RuntimeValue::BigInt(b)) => string_to_bigint(a)?.partial_cmp(b),
+            _ => match (to_numeric(&x), to_numeric(&y)) {
+                (RuntimeValue::BigInt(a), RuntimeValue::BigInt(b)) => a.partial_cmp(&b),
+                (RuntimeValue::BigInt(a), RuntimeValue::Number(b)) => a.partial_cmp_f64(b),
+                (RuntimeValue::Number(a), RuntimeValue::BigInt(b)) => b.partial_cmp_f64(a).map(Ordering::reverse),
+                (RuntimeValue::Number(a), RuntimeValue::Number(b)) => a.partial_cmp(&b),
+                _ => None,
+            },
+        };
+
+        ordering.map(|ordering| ordering == Ordering::Less)
+    }
+}
+
+impl fmt::Display for RuntimeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", to_string(self))
+    }
+}
@@ -1,5 +1,8 @@
 pub mod core;
+pub mod error;
+mod unicode;
 #[cfg(test)]
 pub mod tests;
 
-pub use core::Tokenizer;
\ No newline at end of file
+pub use core::Tokenizer;
+pub use unicode::{is_line_terminator, is_whitespace};
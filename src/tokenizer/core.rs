@@ -1,8 +1,18 @@
 use crate::common::*;
+use super::error::{TokenizeError, TokenizeErrorKind};
+use super::unicode::{self, is_line_terminator, is_whitespace};
 
 pub struct Tokenizer {
     source: Vec<char>,
     position: usize,
+    // byte offset of every char in `source`, plus the total length in bytes
+    byte_offsets: Vec<usize>,
+    // char positions at which each line starts
+    line_starts: Vec<usize>,
+    // for every template substitution `${` being tokenized, the number of `{` opened inside it
+    template_braces: Vec<usize>,
+    // whether a line terminator was skipped since the last token, for ASI
+    newline_before: bool,
 }
 
 enum CommentType {
@@ -14,17 +24,49 @@ impl Tokenizer {
     pub fn new(source: String) -> Self {
         println!("--- Source Provided ---\n{}", source);
         println!("-----------------------\n");
+        let source: Vec<char> = source.chars().collect();
+
+        let mut byte_offsets = Vec::with_capacity(source.len() + 1);
+        let mut line_starts = vec![0];
+        let mut offset = 0;
+        for (i, c) in source.iter().enumerate() {
+            byte_offsets.push(offset);
+            offset += c.len_utf8();
+            // `\r\n` is a single line break
+            let ends_line = match c {
+                '\r' => source.get(i + 1) != Some(&'\n'),
+                c => is_line_terminator(*c),
+            };
+            if ends_line {
+                line_starts.push(i + 1);
+            }
+        }
+        byte_offsets.push(offset);
+
         Self {
-            source: source.chars().collect(),
+            source,
             position: 0,
+            byte_offsets,
+            line_starts,
+            template_braces: vec![],
+            newline_before: false,
         }
     }
 
-    pub fn tokenize(&mut self) -> Vec<Token> {
+    /// Splits the source into tokens.
+    ///
+    /// Tokenizing goes on after an error so that all of them are reported at once.
+    pub fn tokenize(&mut self) -> Result<Vec<Token>, Vec<TokenizeError>> {
         let mut tokens = Vec::<Token>::new();
+        let mut errors = Vec::<TokenizeError>::new();
 
         while !self.is_end() {
-            if self.is_whitespace(self.position) {
+            if is_line_terminator(self.peek()) {
+                self.newline_before = true;
+                self.increment_position();
+                continue;
+            }
+            if is_whitespace(self.peek()) {
                 self.increment_position();
                 continue;
             }
@@ -36,62 +78,110 @@ impl Tokenizer {
                         continue;
                     }
                     CommentType::Block => {
-                        if let Err(error_message) = self.skip_block_comment() {
-                            panic!("{}", error_message);
+                        if let Err(error) = self.skip_block_comment() {
+                            errors.push(error);
                         }
                         continue;
                     }
                 }
             }
 
-            if let Some(token) = self.consume_if_comparison_operator() {
-                tokens.push(token);
+            if let Some(token) = self.consume_if_operator() {
+                self.push_token(&mut tokens, token);
+                continue;
             }
 
-            if self.is_end() {
-                break;
+            if let Some(token) = self.consume_if_comparison_operator() {
+                self.push_token(&mut tokens, token);
+                continue;
             }
 
             let c = self.peek();
+            // `.5` is a number, not a `.` followed by one
+            if c == '.' && self.peek_next().is_some_and(|c| c.is_ascii_digit()) {
+                match self.consume_number() {
+                    Ok(token) => self.push_token(&mut tokens, token),
+                    Err(error) => errors.push(error),
+                }
+                continue;
+            }
+
             let token_type: TokenType = TokenType::from(c);
             match token_type {
                 TokenType::Unsupported(_) => {
-                    if ('0'..='9').contains(&c) || '.' == c {
-                        tokens.push(self.consume_number());
+                    let result = if c.is_ascii_digit() {
+                        self.consume_number()
+                    } else if is_identifier_start(c) || c == '\\' {
+                        self.consume_identifier()
+                    } else if c == '"' || c == '\'' {
+                        self.consume_string()
+                    } else if c == '`' {
+                        self.consume_template()
+                    } else {
+                        errors.push(TokenizeError::new(
+                            TokenizeErrorKind::UnsupportedCharacter(c),
+                            self.span(self.position, self.position + 1),
+                        ));
+                        self.increment_position();
                         continue;
-                    }
+                    };
 
-                    if ('a'..='z').contains(&c) || ('A'..='Z').contains(&c) || c == '_' {
-                        tokens.push(self.consume_identifier());
-                        continue;
+                    match result {
+                        Ok(token) => self.push_token(&mut tokens, token),
+                        Err(error) => errors.push(error),
                     }
-
-                    if c == '"' || c == '\'' || c == '`' {
-                        tokens.push(self.consume_string());
-                        continue;
+                    continue;
+                }
+                TokenType::RightCurlyBrace if self.template_braces.last() == Some(&0) => {
+                    match self.consume_template() {
+                        Ok(token) => self.push_token(&mut tokens, token),
+                        Err(error) => errors.push(error),
+                    }
+                    continue;
+                }
+                kind => {
+                    match kind {
+                        TokenType::LeftCurlyBrace => {
+                            if let Some(braces) = self.template_braces.last_mut() {
+                                *braces += 1;
+                            }
+                        }
+                        TokenType::RightCurlyBrace => {
+                            if let Some(braces) = self.template_braces.last_mut() {
+                                *braces -= 1;
+                            }
+                        }
+                        _ => (),
                     }
+
+                    let token = Token::new(kind, self.span(self.position, self.position + 1));
+                    self.push_token(&mut tokens, token);
                 }
-                kind => tokens.push(Token::new(
-                    kind,
-                    self.position, /* TODO: should be line */
-                )),
             }
 
             self.increment_position();
         }
 
-        tokens.push(Token::new(
-            TokenType::Eof,
-            self.position, /* TODO: should be line */
-        ));
-        tokens
-    }
+        let eof = Token::new(TokenType::Eof, self.span(self.source.len(), self.source.len()));
+        self.push_token(&mut tokens, eof);
 
-    fn is_whitespace(&self, position: usize) -> bool {
-        match self.source[position] {
-            '\n' | ' ' | '\t' | '\r' => true,
-            _ => false,
+        if !errors.is_empty() {
+            return Err(errors);
         }
+
+        Ok(tokens)
+    }
+
+    fn push_token(&mut self, tokens: &mut Vec<Token>, mut token: Token) {
+        token.newline_before = std::mem::take(&mut self.newline_before);
+        tokens.push(token);
+    }
+
+    fn span(&self, start: usize, end: usize) -> Span {
+        let line = self.line_starts.partition_point(|&line_start| line_start <= start);
+        let column = start - self.line_starts[line - 1] + 1;
+
+        Span::new(self.byte_offsets[start], self.byte_offsets[end], line, column)
     }
 
     fn is_end(&self) -> bool {
@@ -106,6 +196,10 @@ impl Tokenizer {
         self.source[self.position]
     }
 
+    fn peek_is(&self, c: char) -> bool {
+        !self.is_end() && self.peek() == c
+    }
+
     fn peek_next(&self) -> Option<char> {
         if self.source.len() <= self.position + 1 {
             return None;
@@ -114,80 +208,425 @@ impl Tokenizer {
         Some(self.source[self.position + 1])
     }
 
-    fn consume_identifier(&mut self) -> Token {
+    fn consume_identifier(&mut self) -> Result<Token, TokenizeError> {
+        let start = self.position;
+        let (identifier, has_escape) = match self.scan_identifier() {
+            Ok(result) => result,
+            Err(error) => {
+                // skip the rest of the identifier so it isn't tokenized again
+                while !self.is_end() && (is_identifier_part(self.peek()) || self.peek() == '\\') {
+                    self.increment_position();
+                }
+                return Err(error);
+            }
+        };
+
+        let token_type = TokenType::from(&identifier.chars().collect::<Vec<char>>()[..]);
+        if has_escape && !matches!(token_type, TokenType::Identifier(_)) {
+            return Err(TokenizeError::new(
+                TokenizeErrorKind::EscapedKeyword(identifier),
+                self.span(start, self.position),
+            ));
+        }
+
+        Ok(Token::new(token_type, self.span(start, self.position)))
+    }
+
+    /// Scans an identifier name, returning it with its `\u` escapes interpreted and
+    /// whether it had any.
+    fn scan_identifier(&mut self) -> Result<(String, bool), TokenizeError> {
         let mut identifier = String::new();
+        let mut has_escape = false;
+
+        while !self.is_end() {
+            let c = self.peek();
+            let is_valid = |c| if identifier.is_empty() { is_identifier_start(c) } else { is_identifier_part(c) };
+
+            if c != '\\' {
+                if !is_valid(c) {
+                    break;
+                }
+                identifier.push(c);
+                self.increment_position();
+                continue;
+            }
 
-        while !self.is_end() && (self.peek().is_ascii_alphanumeric() || self.peek() == '_') {
-            identifier.push(self.peek());
+            let escape_start = self.position;
+            self.increment_position();
+            if !self.peek_is('u') {
+                if !self.is_end() {
+                    self.increment_position();
+                }
+                return Err(self.escape_error(escape_start));
+            }
             self.increment_position();
-        }
 
-        let token_type = TokenType::from(&identifier.chars().collect::<Vec<char>>()[..]);
+            // unlike in strings, every escape is a whole code point and surrogate pairs are not joined
+            let is_braced = self.peek_is('{');
+            let escaped = self.consume_unicode_escape(escape_start)?;
+            if (!is_braced && escaped as u32 > 0xFFFF) || !is_valid(escaped) {
+                return Err(self.escape_error(escape_start));
+            }
 
-        Token::new(token_type, self.position /* TODO: should be line */)
+            identifier.push(escaped);
+            has_escape = true;
+        }
+
+        Ok((identifier, has_escape))
     }
 
-    fn consume_string(&mut self) -> Token {
+    fn consume_string(&mut self) -> Result<Token, TokenizeError> {
+        let start = self.position;
         let mut string = String::new();
         let quote = self.peek();
         self.increment_position();
 
+        // the first invalid escape is reported once the whole string is consumed
+        let mut escape_error = None;
         while !self.is_end() && self.peek() != quote {
-            string.push(self.peek());
-            self.increment_position();
+            match self.peek() {
+                '\n' | '\r' => break,
+                '\\' => match self.consume_escape(false) {
+                    Ok(escaped) => string.extend(escaped),
+                    Err(error) => {
+                        escape_error.get_or_insert(error);
+                    }
+                },
+                c => {
+                    string.push(c);
+                    self.increment_position();
+                }
+            }
         }
 
-        if self.is_end() {
-            panic!("string {} has no closing quote {}", string, quote);
+        if self.is_end() || self.peek() != quote {
+            return Err(TokenizeError::new(
+                TokenizeErrorKind::UnterminatedString(quote),
+                self.span(start, self.position),
+            ));
         }
 
-        let token_type = TokenType::Literal(Literal::String(String::from(string)));
         self.increment_position();
+        if let Some(error) = escape_error {
+            return Err(error);
+        }
 
-        Token::new(token_type, self.position /* TODO: should be line */)
+        let token_type = TokenType::Literal(Literal::String(string));
+        Ok(Token::new(token_type, self.span(start, self.position)))
     }
 
-    fn consume_number(&mut self) -> Token {
+    /// Consumes a template literal part, starting either at the opening `` ` `` or at
+    /// the `}` closing a substitution, up to the closing `` ` `` or the next `${`.
+    fn consume_template(&mut self) -> Result<Token, TokenizeError> {
         let start = self.position;
-        self.position += 1;
+        let is_head = self.peek() == '`';
+        self.increment_position();
+
+        let mut cooked = String::new();
+        let mut escape_error = None;
+        loop {
+            if self.is_end() {
+                return Err(TokenizeError::new(
+                    TokenizeErrorKind::UnterminatedTemplate,
+                    self.span(start, self.position),
+                ));
+            }
 
-        while self.peek().is_ascii_digit() {
-            self.position += 1;
+            match self.peek() {
+                '`' => {
+                    self.increment_position();
+                    if !is_head {
+                        self.template_braces.pop();
+                    }
+                    break;
+                }
+                '$' if self.peek_next() == Some('{') => {
+                    self.position += 2;
+                    if is_head {
+                        self.template_braces.push(0);
+                    }
+                    break;
+                }
+                '\\' => match self.consume_escape(true) {
+                    Ok(escaped) => cooked.extend(escaped),
+                    Err(error) => {
+                        escape_error.get_or_insert(error);
+                    }
+                },
+                // line terminators inside templates are normalized to \n
+                '\r' => {
+                    self.increment_position();
+                    if self.peek_is('\n') {
+                        self.increment_position();
+                    }
+                    cooked.push('\n');
+                }
+                c => {
+                    cooked.push(c);
+                    self.increment_position();
+                }
+            }
         }
 
-        if self.peek() == '.' {
-            self.position += 1;
-            while !self.is_end() && self.peek().is_ascii_digit() {
-                self.position += 1;
+        if let Some(error) = escape_error {
+            return Err(error);
+        }
+
+        let is_tail = self.source[self.position - 1] == '`';
+        let token_type = match (is_head, is_tail) {
+            (true, true) => TokenType::NoSubstitutionTemplate(cooked),
+            (true, false) => TokenType::TemplateHead(cooked),
+            (false, false) => TokenType::TemplateMiddle(cooked),
+            (false, true) => TokenType::TemplateTail(cooked),
+        };
+
+        Ok(Token::new(token_type, self.span(start, self.position)))
+    }
+
+    /// Consumes an escape sequence starting at `\` and returns the char it stands for.
+    ///
+    /// Line continuations produce no chars. Legacy octal escapes are accepted in
+    /// strings since scripts are not in strict mode, templates never allow them.
+    fn consume_escape(&mut self, template: bool) -> Result<Option<char>, TokenizeError> {
+        let start = self.position;
+        self.increment_position();
+
+        if self.is_end() {
+            return Ok(None);
+        }
+
+        let c = self.peek();
+        self.increment_position();
+
+        let escaped = match c {
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{b}',
+            '\r' => {
+                if !self.is_end() && self.peek() == '\n' {
+                    self.increment_position();
+                }
+                return Ok(None);
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => return Ok(None),
+            '0'..='9' if template => {
+                if c != '0' || (!self.is_end() && self.peek().is_ascii_digit()) {
+                    // report the whole sequence a string would read as one octal escape
+                    if c <= '7' {
+                        self.consume_legacy_octal(c);
+                    }
+                    return Err(self.escape_error(start));
+                }
+                '\0'
             }
+            '0'..='7' => char::from_u32(self.consume_legacy_octal(c)).unwrap_or(char::REPLACEMENT_CHARACTER),
+            'x' => self
+                .consume_hex_digits(2)
+                .and_then(char::from_u32)
+                .ok_or_else(|| self.escape_error(start))?,
+            'u' => self.consume_unicode_escape(start)?,
+            c => c,
+        };
+
+        Ok(Some(escaped))
+    }
+
+    /// Consumes the rest of a legacy octal escape starting with `first` and returns its value.
+    fn consume_legacy_octal(&mut self, first: char) -> u32 {
+        let mut value = first.to_digit(8).unwrap_or(0);
+        // \0 to \377, the longest sequence that stays below 256
+        let max_len = if first <= '3' { 3 } else { 2 };
+        let mut len = 1;
+        while len < max_len && !self.is_end() && self.peek().is_digit(8) {
+            value = value * 8 + self.peek().to_digit(8).unwrap_or(0);
+            self.increment_position();
+            len += 1;
         }
+        value
+    }
 
-        let num_str: String = self.source[start..self.position].iter().collect();
+    /// Consumes the part of `\uXXXX` or `\u{X...}` after the `u`.
+    ///
+    /// Surrogate pairs written as two escapes are joined, a lone surrogate
+    /// can't be stored in a Rust string and becomes U+FFFD.
+    fn consume_unicode_escape(&mut self, start: usize) -> Result<char, TokenizeError> {
+        let value = if !self.is_end() && self.peek() == '{' {
+            self.increment_position();
+            let digits_start = self.position;
+            let mut value: u32 = 0;
+            while !self.is_end() && self.peek().is_ascii_hexdigit() {
+                value = value.saturating_mul(16).saturating_add(self.peek().to_digit(16).unwrap_or(0));
+                self.increment_position();
+            }
 
-        let token_type;
-        if num_str.ends_with('.') {
-            token_type = TokenType::Literal(Literal::Number(
-                num_str[..num_str.len() - 1]
-                    .parse()
-                    .expect("number ending with . should be parsed"),
-            ));
+            if self.position == digits_start || self.is_end() || self.peek() != '}' || value > 0x10FFFF {
+                return Err(self.escape_error(start));
+            }
+            self.increment_position();
+            value
         } else {
-            token_type = TokenType::Literal(Literal::Number(
-                num_str
-                    .parse()
-                    .expect("consume_number: can't parse a number"),
+            self.consume_hex_digits(4).ok_or_else(|| self.escape_error(start))?
+        };
+
+        if (0xD800..0xDC00).contains(&value) {
+            let checkpoint = self.position;
+            if self.peek_is('\\') && self.peek_next() == Some('u') {
+                self.position += 2;
+                match self.consume_hex_digits(4) {
+                    Some(low) if (0xDC00..0xE000).contains(&low) => {
+                        let code_point = 0x10000 + ((value - 0xD800) << 10) + (low - 0xDC00);
+                        return Ok(char::from_u32(code_point).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    _ => self.position = checkpoint,
+                }
+            }
+        }
+
+        Ok(char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn consume_hex_digits(&mut self, count: usize) -> Option<u32> {
+        let mut value = 0;
+        for _ in 0..count {
+            let digit = self.source.get(self.position)?.to_digit(16)?;
+            value = value * 16 + digit;
+            self.increment_position();
+        }
+
+        Some(value)
+    }
+
+    fn escape_error(&self, start: usize) -> TokenizeError {
+        let escape: String = self.source[start..self.position].iter().collect();
+        TokenizeError::new(
+            TokenizeErrorKind::InvalidEscape(escape),
+            self.span(start, self.position),
+        )
+    }
+
+    fn consume_number(&mut self) -> Result<Token, TokenizeError> {
+        let start = self.position;
+        let number = self.scan_number();
+
+        // a numeric literal can't be directly followed by an identifier or a digit
+        let is_followed_by_identifier = !self.is_end()
+            && (is_identifier_start(self.peek()) || self.peek().is_ascii_digit() || self.peek() == '\\');
+        if number.is_none() || is_followed_by_identifier {
+            // take the rest of the malformed literal so it is reported as a whole
+            while !self.is_end() && (is_identifier_part(self.peek()) || self.peek() == '.') {
+                self.increment_position();
+            }
+
+            let literal: String = self.source[start..self.position].iter().collect();
+            return Err(TokenizeError::new(
+                TokenizeErrorKind::InvalidNumber(literal),
+                self.span(start, self.position),
             ));
         }
 
-        Token::new(token_type, self.position /* TODO: should be line */)
+        let token_type = TokenType::Literal(number.unwrap_or(Literal::Number(f64::NAN)));
+        Ok(Token::new(token_type, self.span(start, self.position)))
+    }
+
+    /// Scans a numeric literal: decimal with optional fraction and exponent,
+    /// `0x`/`0o`/`0b` prefixed integers and legacy `0`-prefixed octals, all of
+    /// them with `_` separators except the legacy ones. Integers other than the
+    /// legacy ones become BigInts with an `n` suffix.
+    fn scan_number(&mut self) -> Option<Literal> {
+        let radix = match (self.peek(), self.peek_next()) {
+            ('0', Some('x' | 'X')) => Some(16),
+            ('0', Some('o' | 'O')) => Some(8),
+            ('0', Some('b' | 'B')) => Some(2),
+            _ => None,
+        };
+        if let Some(radix) = radix {
+            self.position += 2;
+            let digits = self.consume_digits(radix)?;
+            if self.peek_is('n') {
+                self.increment_position();
+                return BigInt::parse(&digits, radix).map(Literal::BigInt);
+            }
+            return Some(Literal::Number(integer_from_digits(&digits, radix)));
+        }
+
+        let mut literal = String::new();
+        let is_legacy = self.peek() == '0' && self.peek_next().is_some_and(|c| c.is_ascii_digit());
+        if is_legacy {
+            // legacy octal like 017, or decimal if any digit is 8 or 9 like 019
+            while !self.is_end() && self.peek().is_ascii_digit() {
+                literal.push(self.peek());
+                self.increment_position();
+            }
+
+            if literal.chars().all(|c| c.is_digit(8)) {
+                // an octal has no fraction or exponent, 017.5 isn't 017 then .5
+                if self.peek_is('.') || self.peek_is('e') || self.peek_is('E') {
+                    return None;
+                }
+                return Some(Literal::Number(integer_from_digits(&literal, 8)));
+            }
+        } else if self.peek() == '0' {
+            // no separator can follow a leading 0
+            literal.push('0');
+            self.increment_position();
+        } else if self.peek() != '.' {
+            literal += &self.consume_digits(10)?;
+        }
+
+        if !is_legacy && !literal.is_empty() && self.peek_is('n') {
+            self.increment_position();
+            return BigInt::parse(&literal, 10).map(Literal::BigInt);
+        }
+
+        if self.peek_is('.') {
+            self.increment_position();
+            literal.push('.');
+            if !self.is_end() && self.peek().is_ascii_digit() {
+                literal += &self.consume_digits(10)?;
+            }
+        }
+
+        if self.peek_is('e') || self.peek_is('E') {
+            self.increment_position();
+            literal.push('e');
+            if self.peek_is('+') || self.peek_is('-') {
+                literal.push(self.peek());
+                self.increment_position();
+            }
+            literal += &self.consume_digits(10)?;
+        }
+
+        literal.parse().ok().map(Literal::Number)
+    }
+
+    /// Consumes one or more digits of `radix`, allowing single `_` separators between them.
+    fn consume_digits(&mut self, radix: u32) -> Option<String> {
+        let mut digits = String::new();
+        while !self.is_end() {
+            let c = self.peek();
+            if c.is_digit(radix) {
+                digits.push(c);
+                self.increment_position();
+            } else if c == '_' && !digits.is_empty() && self.peek_next().is_some_and(|c| c.is_digit(radix)) {
+                self.increment_position();
+            } else {
+                break;
+            }
+        }
+
+        if digits.is_empty() || self.peek_is('_') {
+            return None;
+        }
+
+        Some(digits)
     }
 
     fn is_comment(&self) -> Option<CommentType> {
         let c = self.peek();
-        let c_next = match self.peek_next() {
-            Some(c) => c,
-            None => return None,
-        };
+        let c_next = self.peek_next()?;
         if c == '/' {
             if c_next == '/' {
                 return Some(CommentType::Line);
@@ -201,12 +640,49 @@ impl Tokenizer {
         None
     }
 
+    /// Consumes the longest multi-char arithmetic, update, shift, logical or assignment operator.
+    fn consume_if_operator(&mut self) -> Option<Token> {
+        const OPERATORS: [(&str, TokenType); 24] = [
+            (">>>=", TokenType::UnsignedRightShiftAssign),
+            (">>>", TokenType::UnsignedRightShift),
+            ("<<=", TokenType::LeftShiftAssign),
+            (">>=", TokenType::RightShiftAssign),
+            ("**=", TokenType::StarStarAssign),
+            ("&&=", TokenType::AmpersandAmpersandAssign),
+            ("||=", TokenType::PipePipeAssign),
+            ("??=", TokenType::QuestionQuestionAssign),
+            ("&&", TokenType::AmpersandAmpersand),
+            ("||", TokenType::PipePipe),
+            ("??", TokenType::QuestionQuestion),
+            ("++", TokenType::PlusPlus),
+            ("--", TokenType::MinusMinus),
+            ("**", TokenType::StarStar),
+            ("<<", TokenType::LeftShift),
+            (">>", TokenType::RightShift),
+            ("+=", TokenType::PlusAssign),
+            ("-=", TokenType::MinusAssign),
+            ("*=", TokenType::StarAssign),
+            ("/=", TokenType::SlashAssign),
+            ("%=", TokenType::PercentAssign),
+            ("&=", TokenType::AmpersandAssign),
+            ("|=", TokenType::PipeAssign),
+            ("^=", TokenType::CaretAssign),
+        ];
+
+        let start = self.position;
+        let rest = &self.source[start..];
+        let (operator, token_type) = OPERATORS
+            .iter()
+            .find(|(operator, _)| rest.iter().take(operator.len()).copied().eq(operator.chars()))?;
+
+        self.position += operator.len();
+        Some(Token::new(token_type.clone(), self.span(start, self.position)))
+    }
+
     fn consume_if_comparison_operator(&mut self) -> Option<Token> {
+        let start = self.position;
         let c = self.peek();
-        let c_next = match self.peek_next() {
-            Some(c) => c,
-            None => return None,
-        };
+        let c_next = self.peek_next()?;
 
         let token_type = match (c, c_next) {
             ('>', '=') => {
@@ -258,24 +734,18 @@ impl Tokenizer {
             _ => None,
         };
 
-        match token_type {
-            Some(token_type) => Some(Token::new(
-                token_type,
-                self.position, /* TODO: should be line */
-            )),
-            None => None,
-        }
+        token_type.map(|token_type| Token::new(token_type, self.span(start, self.position)))
     }
 
     fn skip_line_comment(&mut self) {
-        while !self.is_end() && self.peek() != '\n' {
+        // the line break itself is left for the whitespace handling
+        while !self.is_end() && !is_line_terminator(self.peek()) {
             self.increment_position();
         }
-
-        self.increment_position();
     }
 
-    fn skip_block_comment(&mut self) -> Result<(), String> {
+    fn skip_block_comment(&mut self) -> Result<(), TokenizeError> {
+        let start = self.position;
         self.position += 2;
 
         let mut depth = 1;
@@ -296,9 +766,51 @@ impl Tokenizer {
         }
 
         if depth != 0 {
-            return Err("unterminated block comment".into());
+            return Err(TokenizeError::new(
+                TokenizeErrorKind::UnterminatedBlockComment,
+                self.span(start, start + 2),
+            ));
+        }
+
+        // a comment spanning lines separates tokens like a line break does
+        if self.source[start..self.position].iter().any(|c| is_line_terminator(*c)) {
+            self.newline_before = true;
         }
 
         Ok(())
     }
 }
+
+fn is_identifier_start(c: char) -> bool {
+    unicode::is_id_start(c) || matches!(c, '$' | '_')
+}
+
+fn is_identifier_part(c: char) -> bool {
+    // ZWNJ and ZWJ
+    unicode::is_id_continue(c) || matches!(c, '$' | '\u{200C}' | '\u{200D}')
+}
+
+/// Converts integer digits of a power of two `radix` to the nearest f64.
+///
+/// Bits that don't fit into 128 bits are only remembered as "something was
+/// there", which is enough for round-half-to-even to be exact.
+fn integer_from_digits(digits: &str, radix: u32) -> f64 {
+    let bits_per_digit = radix.trailing_zeros();
+    let mut mantissa: u128 = 0;
+    let mut exponent = 0;
+    let mut sticky = 0;
+
+    for digit in digits.chars().filter_map(|c| c.to_digit(radix)) {
+        for i in (0..bits_per_digit).rev() {
+            let bit = ((digit >> i) & 1) as u128;
+            if mantissa >> 127 == 0 {
+                mantissa = (mantissa << 1) | bit;
+            } else {
+                exponent += 1;
+                sticky |= bit;
+            }
+        }
+    }
+
+    (mantissa | sticky) as f64 * 2f64.powi(exponent)
+}
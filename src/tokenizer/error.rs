@@ -0,0 +1,55 @@
+use std::fmt;
+use crate::common::{Diagnostic, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenizeErrorKind {
+    UnterminatedBlockComment,
+    UnterminatedString(char),
+    UnterminatedTemplate,
+    InvalidEscape(String),
+    InvalidNumber(String),
+    UnsupportedCharacter(char),
+    EscapedKeyword(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenizeError {
+    pub kind: TokenizeErrorKind,
+    pub span: Span,
+}
+
+impl TokenizeError {
+    pub fn new(kind: TokenizeErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+impl fmt::Display for TokenizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TokenizeErrorKind::UnterminatedBlockComment => write!(f, "unterminated block comment"),
+            TokenizeErrorKind::UnterminatedString(quote) => write!(f, "string has no closing quote {}", quote),
+            TokenizeErrorKind::UnterminatedTemplate => write!(f, "unterminated template literal"),
+            TokenizeErrorKind::InvalidEscape(escape) => write!(f, "invalid escape sequence {}", escape),
+            TokenizeErrorKind::InvalidNumber(number) => write!(f, "invalid number literal {}", number),
+            TokenizeErrorKind::UnsupportedCharacter(c) => write!(f, "unsupported character {:?}", c),
+            TokenizeErrorKind::EscapedKeyword(keyword) => write!(f, "keyword {} can't contain escape sequences", keyword),
+        }
+    }
+}
+
+impl From<TokenizeError> for Diagnostic {
+    fn from(error: TokenizeError) -> Self {
+        let code = match error.kind {
+            TokenizeErrorKind::UnterminatedBlockComment => "E0001",
+            TokenizeErrorKind::UnterminatedString(_) => "E0002",
+            TokenizeErrorKind::InvalidNumber(_) => "E0003",
+            TokenizeErrorKind::UnsupportedCharacter(_) => "E0004",
+            TokenizeErrorKind::InvalidEscape(_) => "E0005",
+            TokenizeErrorKind::UnterminatedTemplate => "E0006",
+            TokenizeErrorKind::EscapedKeyword(_) => "E0007",
+        };
+
+        Diagnostic::error(code, error.to_string(), error.span)
+    }
+}
@@ -1,9 +1,10 @@
 use crate::common::*;
 use super::core::Tokenizer;
+use super::error::{TokenizeError, TokenizeErrorKind};
 
 fn get_token_types(source: &str) -> Vec<TokenType> {
     let mut tokenizer = Tokenizer::new(source.into());
-    tokenizer.tokenize().into_iter().map(|t| t.kind).collect()
+    tokenizer.tokenize().unwrap().into_iter().map(|t| t.kind).collect()
 }
 
 #[test]
@@ -11,14 +12,14 @@ fn test_general_cases() {
     assert_eq!(
         get_token_types("1 + 5 * (1 + 9);"),
         vec![
-            TokenType::Literal(Literal::Number(1.0 as f64)),
+            TokenType::Literal(Literal::Number(1.0)),
             TokenType::Plus,
-            TokenType::Literal(Literal::Number(5.0 as f64)),
+            TokenType::Literal(Literal::Number(5.0)),
             TokenType::Star,
             TokenType::LeftParen,
-            TokenType::Literal(Literal::Number(1.0 as f64)),
+            TokenType::Literal(Literal::Number(1.0)),
             TokenType::Plus,
-            TokenType::Literal(Literal::Number(9.0 as f64)),
+            TokenType::Literal(Literal::Number(9.0)),
             TokenType::RightParen,
             TokenType::Semicolon,
             TokenType::Eof
@@ -67,12 +68,12 @@ fn test_number_literals() {
     assert_eq!(
         get_token_types("123 12.3 0 0.0 .123 123."),
         vec![
-            TokenType::Literal(Literal::Number(123 as f64)),
-            TokenType::Literal(Literal::Number(12.3 as f64)),
-            TokenType::Literal(Literal::Number(0 as f64)),
-            TokenType::Literal(Literal::Number(0.0 as f64)),
-            TokenType::Literal(Literal::Number(0.123 as f64)),
-            TokenType::Literal(Literal::Number(123 as f64)),
+            TokenType::Literal(Literal::Number(123.0)),
+            TokenType::Literal(Literal::Number(12.3)),
+            TokenType::Literal(Literal::Number(0.0)),
+            TokenType::Literal(Literal::Number(0.0)),
+            TokenType::Literal(Literal::Number(0.123)),
+            TokenType::Literal(Literal::Number(123.0)),
             TokenType::Eof
         ]
     );
@@ -83,14 +84,14 @@ fn test_literals() {
     assert_eq!(
         get_token_types(r#"123 false 'hello' true null undefined "hello" `hello`"#),
         vec![
-            TokenType::Literal(Literal::Number(123 as f64)),
+            TokenType::Literal(Literal::Number(123.0)),
             TokenType::Literal(Literal::Boolean(false)),
             TokenType::Literal(Literal::String("hello".into())),
             TokenType::Literal(Literal::Boolean(true)),
             TokenType::Literal(Literal::Null),
             TokenType::Literal(Literal::Undefined),
             TokenType::Literal(Literal::String("hello".into())),
-            TokenType::Literal(Literal::String("hello".into())),
+            TokenType::NoSubstitutionTemplate("hello".into()),
             TokenType::Eof
         ]
     );
@@ -284,3 +285,611 @@ fn test_function_calls() {
         ]
     );
 }
+
+#[test]
+fn test_token_spans() {
+    let mut tokenizer = Tokenizer::new("let x = 'é';\n  y >= 10;".into());
+    let spans: Vec<Span> = tokenizer.tokenize().unwrap().into_iter().map(|t| t.span).collect();
+
+    assert_eq!(
+        spans,
+        vec![
+            Span::new(0, 3, 1, 1),
+            Span::new(4, 5, 1, 5),
+            Span::new(6, 7, 1, 7),
+            Span::new(8, 12, 1, 9),
+            Span::new(12, 13, 1, 12),
+            Span::new(16, 17, 2, 3),
+            Span::new(18, 20, 2, 5),
+            Span::new(21, 23, 2, 8),
+            Span::new(23, 24, 2, 10),
+            Span::new(24, 24, 2, 11),
+        ]
+    );
+}
+
+#[test]
+fn test_unterminated_errors() {
+    let mut tokenizer = Tokenizer::new("let x = 'hello;".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err(),
+        vec![TokenizeError::new(TokenizeErrorKind::UnterminatedString('\''), Span::new(8, 15, 1, 9))]
+    );
+
+    let mut tokenizer = Tokenizer::new("let x; /* comment".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err(),
+        vec![TokenizeError::new(TokenizeErrorKind::UnterminatedBlockComment, Span::new(7, 9, 1, 8))]
+    );
+}
+
+#[test]
+fn test_unsupported_characters() {
+    let mut tokenizer = Tokenizer::new("let x = 1 # 2;
+let y = @;".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err(),
+        vec![
+            TokenizeError::new(TokenizeErrorKind::UnsupportedCharacter('#'), Span::new(10, 11, 1, 11)),
+            TokenizeError::new(TokenizeErrorKind::UnsupportedCharacter('@'), Span::new(23, 24, 2, 9)),
+        ]
+    );
+
+    let mut tokenizer = Tokenizer::new("x\u{a7};".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err(),
+        vec![TokenizeError::new(TokenizeErrorKind::UnsupportedCharacter('\u{a7}'), Span::new(1, 3, 1, 2))]
+    );
+}
+
+#[test]
+fn test_errors_do_not_stop_tokenizing() {
+    let mut tokenizer = Tokenizer::new("# let /* \n x = 'a".into());
+    let errors = tokenizer.tokenize().unwrap_err();
+
+    assert_eq!(
+        errors.iter().map(|e| e.kind.clone()).collect::<Vec<TokenizeErrorKind>>(),
+        vec![TokenizeErrorKind::UnsupportedCharacter('#'), TokenizeErrorKind::UnterminatedBlockComment]
+    );
+}
+
+#[test]
+fn test_number_at_end_of_input() {
+    assert_eq!(
+        get_token_types("1"),
+        vec![TokenType::Literal(Literal::Number(1.0)), TokenType::Eof]
+    );
+    assert_eq!(
+        get_token_types("1."),
+        vec![TokenType::Literal(Literal::Number(1.0)), TokenType::Eof]
+    );
+}
+
+#[test]
+fn test_error_to_diagnostic() {
+    let error = TokenizeError::new(TokenizeErrorKind::UnsupportedCharacter('#'), Span::new(10, 11, 1, 11));
+    let diagnostic = Diagnostic::from(error);
+
+    assert_eq!(diagnostic.code, "E0004");
+    assert_eq!(diagnostic.message, "unsupported character '#'");
+    assert_eq!(diagnostic.span, Span::new(10, 11, 1, 11));
+}
+
+fn get_string(source: &str) -> String {
+    match get_token_types(source).remove(0) {
+        TokenType::Literal(Literal::String(value)) => value,
+        other => panic!("expected string literal but got {:?}", other),
+    }
+}
+
+fn get_escape_error(source: &str) -> TokenizeError {
+    let mut tokenizer = Tokenizer::new(source.into());
+    tokenizer.tokenize().unwrap_err().remove(0)
+}
+
+#[test]
+fn test_single_char_escapes() {
+    assert_eq!(get_string(r#""a\"b""#), "a\"b");
+    assert_eq!(get_string(r"'it\'s'"), "it's");
+    assert_eq!(get_string(r"'\\'"), "\\");
+    assert_eq!(get_string(r"'\b\f\n\r\t\v'"), "\u{8}\u{c}\n\r\t\u{b}");
+    assert_eq!(get_string(r"'\q\$\ '"), "q$ ");
+}
+
+#[test]
+fn test_hex_and_unicode_escapes() {
+    assert_eq!(get_string(r"'\x41\x7a'"), "Az");
+    assert_eq!(get_string(r"'\u0041\u00e9'"), "Aé");
+    assert_eq!(get_string(r"'\u{1F600}\u{41}'"), "😀A");
+    assert_eq!(get_string(r"'\uD83D\uDE00'"), "😀");
+    assert_eq!(get_string(r"'\uD83Dx'"), "\u{FFFD}x");
+}
+
+#[test]
+fn test_legacy_octal_escapes() {
+    assert_eq!(get_string(r"'\0'"), "\0");
+    assert_eq!(get_string(r"'\08'"), "\u{0}8");
+    assert_eq!(get_string(r"'\101\7\377\400'"), "A\u{7}\u{ff} 0");
+    assert_eq!(get_string(r"'\8\9'"), "89");
+}
+
+#[test]
+fn test_line_continuations() {
+    assert_eq!(get_string("'hello \\\nworld'"), "hello world");
+    assert_eq!(get_string("'a\\\r\nb'"), "ab");
+    assert_eq!(get_string("'a\\\u{2028}b'"), "ab");
+}
+
+#[test]
+fn test_invalid_escapes() {
+    assert_eq!(
+        get_escape_error(r"let s = 'a\x4g';"),
+        TokenizeError::new(TokenizeErrorKind::InvalidEscape(r"\x4".into()), Span::new(10, 13, 1, 11))
+    );
+    assert_eq!(
+        get_escape_error(r"'\u12'").kind,
+        TokenizeErrorKind::InvalidEscape(r"\u12".into())
+    );
+    assert_eq!(
+        get_escape_error(r"'\u{110000}'").kind,
+        TokenizeErrorKind::InvalidEscape(r"\u{110000".into())
+    );
+    assert_eq!(
+        get_escape_error(r"'\u{}'").kind,
+        TokenizeErrorKind::InvalidEscape(r"\u{".into())
+    );
+
+    // the rest of the source is still tokenized
+    let mut tokenizer = Tokenizer::new(r"'\x' + #".into());
+    assert_eq!(tokenizer.tokenize().unwrap_err().len(), 2);
+}
+
+#[test]
+fn test_unescaped_line_break_in_string() {
+    assert_eq!(
+        get_escape_error("'abc\ndef'").kind,
+        TokenizeErrorKind::UnterminatedString('\'')
+    );
+}
+
+#[test]
+fn test_template_literals() {
+    assert_eq!(
+        get_token_types("`a${x}b${ y + 1 }c`;"),
+        vec![
+            TokenType::TemplateHead("a".into()),
+            TokenType::Identifier("x".into()),
+            TokenType::TemplateMiddle("b".into()),
+            TokenType::Identifier("y".into()),
+            TokenType::Plus,
+            TokenType::Literal(Literal::Number(1.0)),
+            TokenType::TemplateTail("c".into()),
+            TokenType::Semicolon,
+            TokenType::Eof,
+        ]
+    );
+
+    assert_eq!(
+        get_token_types("`${x}`"),
+        vec![
+            TokenType::TemplateHead("".into()),
+            TokenType::Identifier("x".into()),
+            TokenType::TemplateTail("".into()),
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_nested_template_literals() {
+    assert_eq!(
+        get_token_types("`a${ `b${c}` }d` {}"),
+        vec![
+            TokenType::TemplateHead("a".into()),
+            TokenType::TemplateHead("b".into()),
+            TokenType::Identifier("c".into()),
+            TokenType::TemplateTail("".into()),
+            TokenType::TemplateTail("d".into()),
+            TokenType::LeftCurlyBrace,
+            TokenType::RightCurlyBrace,
+            TokenType::Eof,
+        ]
+    );
+
+    assert_eq!(
+        get_token_types("`${ f(function() {}) }`"),
+        vec![
+            TokenType::TemplateHead("".into()),
+            TokenType::Identifier("f".into()),
+            TokenType::LeftParen,
+            TokenType::Function,
+            TokenType::LeftParen,
+            TokenType::RightParen,
+            TokenType::LeftCurlyBrace,
+            TokenType::RightCurlyBrace,
+            TokenType::RightParen,
+            TokenType::TemplateTail("".into()),
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_multi_line_template_literals() {
+    assert_eq!(
+        get_token_types("`line 1\nline 2\r\nline 3 $ \\u{41}\\`\\${}`"),
+        vec![
+            TokenType::NoSubstitutionTemplate("line 1\nline 2\nline 3 $ A`${}".into()),
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_template_literal_errors() {
+    let mut tokenizer = Tokenizer::new("`abc ${x}".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err(),
+        vec![TokenizeError::new(TokenizeErrorKind::UnterminatedTemplate, Span::new(8, 9, 1, 9))]
+    );
+
+    let mut tokenizer = Tokenizer::new("`\\1` `\\08` `\\0`".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err().into_iter().map(|e| e.kind).collect::<Vec<TokenizeErrorKind>>(),
+        vec![
+            TokenizeErrorKind::InvalidEscape("\\1".into()),
+            TokenizeErrorKind::InvalidEscape("\\0".into()),
+        ]
+    );
+
+    let mut tokenizer = Tokenizer::new("`a\\01` `\\377`".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err(),
+        vec![
+            TokenizeError::new(TokenizeErrorKind::InvalidEscape("\\01".into()), Span::new(2, 5, 1, 3)),
+            TokenizeError::new(TokenizeErrorKind::InvalidEscape("\\377".into()), Span::new(8, 12, 1, 9)),
+        ]
+    );
+}
+
+fn get_number(source: &str) -> f64 {
+    match get_token_types(source).remove(0) {
+        TokenType::Literal(Literal::Number(value)) => value,
+        other => panic!("expected number literal but got {:?}", other),
+    }
+}
+
+fn get_number_error(source: &str) -> TokenizeError {
+    let mut tokenizer = Tokenizer::new(source.into());
+    tokenizer.tokenize().unwrap_err().remove(0)
+}
+
+#[test]
+fn test_exponent_literals() {
+    assert_eq!(get_number("1e3"), 1000.0);
+    assert_eq!(get_number("1E3"), 1000.0);
+    assert_eq!(get_number("2.5e+2"), 250.0);
+    assert_eq!(get_number("1e-3"), 0.001);
+    assert_eq!(get_number(".5e-3"), 0.0005);
+    assert_eq!(get_number("1.e2"), 100.0);
+    assert_eq!(get_number("0e5"), 0.0);
+    assert_eq!(get_number("1e400"), f64::INFINITY);
+}
+
+#[test]
+fn test_prefixed_integer_literals() {
+    assert_eq!(get_number("0xFF"), 255.0);
+    assert_eq!(get_number("0Xff"), 255.0);
+    assert_eq!(get_number("0o17"), 15.0);
+    assert_eq!(get_number("0O17"), 15.0);
+    assert_eq!(get_number("0b1010"), 10.0);
+    assert_eq!(get_number("0B1"), 1.0);
+    assert_eq!(get_number("0x0"), 0.0);
+}
+
+#[test]
+fn test_large_prefixed_integer_literals_are_rounded() {
+    // 2^53 + 1 and 2^53 + 3 round half to even
+    assert_eq!(get_number("0x20000000000001"), 9007199254740992.0);
+    assert_eq!(get_number("0x20000000000003"), 9007199254740996.0);
+    assert_eq!(get_number("0x20000000000001_1"), 144115188075855904.0);
+    assert_eq!(get_number(&format!("0b1{}", "0".repeat(140))), 2f64.powi(140));
+    assert_eq!(get_number(&format!("0x{}", "F".repeat(34))), 2f64.powi(136));
+}
+
+#[test]
+fn test_numeric_separators() {
+    assert_eq!(get_number("1_000_000"), 1000000.0);
+    assert_eq!(get_number("1_0.0_1e1_0"), 100100000000.0);
+    assert_eq!(get_number("0xFF_FF"), 65535.0);
+    assert_eq!(get_number("0b1010_0101"), 165.0);
+    assert_eq!(get_number(".0_1"), 0.01);
+}
+
+#[test]
+fn test_legacy_octal_literals() {
+    assert_eq!(get_number("017"), 15.0);
+    assert_eq!(get_number("0777"), 511.0);
+    assert_eq!(get_number("019"), 19.0);
+    assert_eq!(get_number("08.5"), 8.5);
+    assert_eq!(get_number("00"), 0.0);
+    assert_eq!(get_number("019.5"), 19.5);
+    assert_eq!(get_number("09e1"), 90.0);
+    assert_eq!(get_number_error("017.5").kind, TokenizeErrorKind::InvalidNumber("017.5".into()));
+    assert_eq!(get_number_error("017.").kind, TokenizeErrorKind::InvalidNumber("017.".into()));
+    assert_eq!(get_number_error("0777e2").kind, TokenizeErrorKind::InvalidNumber("0777e2".into()));
+}
+
+#[test]
+fn test_malformed_number_literals() {
+    for (source, literal) in [
+        ("0x", "0x"),
+        ("0x;", "0x"),
+        ("0b", "0b"),
+        ("0o8", "0o8"),
+        ("0b12", "0b12"),
+        ("1__0", "1__0"),
+        ("1_", "1_"),
+        ("0_1", "0_1"),
+        ("1._5", "1._5"),
+        ("1_.5", "1_.5"),
+        ("1e", "1e"),
+        ("1e+", "1e+"),
+        ("1e_1", "1e_1"),
+        ("0x_1", "0x_1"),
+        ("07_7", "07_7"),
+        ("3in", "3in"),
+        ("1.toString", "1.toString"),
+        ("1$", "1$"),
+        ("2café", "2café"),
+    ] {
+        assert_eq!(
+            get_number_error(source).kind,
+            TokenizeErrorKind::InvalidNumber(literal.into()),
+            "{}",
+            source
+        );
+    }
+
+    assert_eq!(
+        get_number_error("let x = 1__0;"),
+        TokenizeError::new(TokenizeErrorKind::InvalidNumber("1__0".into()), Span::new(8, 12, 1, 9))
+    );
+}
+
+fn get_bigint(source: &str) -> String {
+    match get_token_types(source).remove(0) {
+        TokenType::Literal(Literal::BigInt(value)) => value.to_string(),
+        other => panic!("expected bigint literal but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bigint_literals() {
+    assert_eq!(get_bigint("0n"), "0");
+    assert_eq!(get_bigint("123n"), "123");
+    assert_eq!(get_bigint("1_000_000n"), "1000000");
+    assert_eq!(get_bigint("0xFFn"), "255");
+    assert_eq!(get_bigint("0o17n"), "15");
+    assert_eq!(get_bigint("0b101n"), "5");
+    assert_eq!(get_bigint("9007199254740993n"), "9007199254740993");
+    assert_eq!(get_bigint("0xFFFFFFFFFFFFFFFFFFFFn"), "1208925819614629174706175");
+}
+
+#[test]
+fn test_malformed_bigint_literals() {
+    for source in ["1.5n", "1e3n", ".5n", "017n", "019n", "01n", "1nn", "1n_"] {
+        assert_eq!(
+            get_number_error(source).kind,
+            TokenizeErrorKind::InvalidNumber(source.into()),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn test_unicode_identifiers() {
+    assert_eq!(
+        get_token_types("let $ = jQuery$; café _x1 ℘ ᢅ π2 a‍b"),
+        vec![
+            TokenType::KeywordLet,
+            TokenType::Identifier("$".into()),
+            TokenType::Assign,
+            TokenType::Identifier("jQuery$".into()),
+            TokenType::Semicolon,
+            TokenType::Identifier("café".into()),
+            TokenType::Identifier("_x1".into()),
+            TokenType::Identifier("℘".into()),
+            TokenType::Identifier("ᢅ".into()),
+            TokenType::Identifier("π2".into()),
+            TokenType::Identifier("a\u{200D}b".into()),
+            TokenType::Eof,
+        ]
+    );
+
+    // combining marks and digits can continue an identifier but not start one
+    assert_eq!(get_token_types("e\u{301}")[0], TokenType::Identifier("e\u{301}".into()));
+    assert!(Tokenizer::new("\u{301}e".into()).tokenize().is_err());
+}
+
+#[test]
+fn test_identifier_escapes() {
+    assert_eq!(
+        get_token_types(concat!(r"\u{61}bc a\u{62}c \u{1D400} caf\u{E9} \u{24}", " \\", "u0078y")),
+        vec![
+            TokenType::Identifier("abc".into()),
+            TokenType::Identifier("abc".into()),
+            TokenType::Identifier("\u{1D400}".into()),
+            TokenType::Identifier("café".into()),
+            TokenType::Identifier("$".into()),
+            TokenType::Identifier("xy".into()),
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_identifier_escapes() {
+    for (source, escape) in [
+        // an escaped digit can't start an identifier
+        (r"\u{30}abc", r"\u{30}"),
+        (r"a\u{2D}", r"\u{2D}"),
+        (r"a\x41", r"\x"),
+        (r"a\u00", r"\u00"),
+        (r"a\u{110000}", r"\u{110000"),
+        // surrogate pairs are not joined in identifiers
+        (concat!(r"\", "uD835", r"\", "uDC00"), concat!(r"\", "uD835", r"\", "uDC00")),
+    ] {
+        let mut tokenizer = Tokenizer::new(source.into());
+        let errors = tokenizer.tokenize().unwrap_err();
+        assert_eq!(errors.len(), 1, "{}", source);
+        assert_eq!(errors[0].kind, TokenizeErrorKind::InvalidEscape(escape.into()), "{}", source);
+    }
+}
+
+#[test]
+fn test_escaped_keywords() {
+    let mut tokenizer = Tokenizer::new(r"l\u{65}t x = 1;".into());
+    assert_eq!(
+        tokenizer.tokenize().unwrap_err(),
+        vec![TokenizeError::new(TokenizeErrorKind::EscapedKeyword("let".into()), Span::new(0, 8, 1, 1))]
+    );
+}
+
+#[test]
+fn test_newline_before_tokens() {
+    let mut tokenizer = Tokenizer::new("a b\nc // comment\nd /* one line */ e /*\n*/ f\r\ng `\n` h".into());
+    let newlines: Vec<bool> = tokenizer.tokenize().unwrap().iter().map(|t| t.newline_before).collect();
+
+    // a b c d e f g `\n` h Eof
+    assert_eq!(newlines, vec![false, false, true, true, false, true, true, false, false, false]);
+}
+
+#[test]
+fn test_unicode_whitespace() {
+    // tab, vertical tab, form feed, NBSP, BOM, ogham space, en quad, ideographic space
+    for space in ['\t', '\u{B}', '\u{C}', '\u{A0}', '\u{FEFF}', '\u{1680}', '\u{2000}', '\u{3000}'] {
+        let mut tokenizer = Tokenizer::new(format!("{}a{}b", space, space));
+        let tokens = tokenizer.tokenize().unwrap_or_else(|errors| panic!("{:?}: {:?}", space, errors));
+        assert_eq!(tokens.len(), 3, "{:?}", space);
+        assert!(tokens.iter().all(|token| !token.newline_before), "{:?}", space);
+        assert_eq!(tokens[1].span.line, 1, "{:?}", space);
+    }
+
+    // a file saved with a byte order mark
+    assert_eq!(get_token_types("\u{FEFF}x;"), vec![TokenType::Identifier("x".into()), TokenType::Semicolon, TokenType::Eof]);
+}
+
+#[test]
+fn test_unicode_line_terminators() {
+    for terminator in ["\n", "\r", "\r\n", "\u{2028}", "\u{2029}"] {
+        let source = format!("a{}b // comment{}c", terminator, terminator);
+        let mut tokenizer = Tokenizer::new(source);
+        let tokens = tokenizer.tokenize().unwrap_or_else(|errors| panic!("{:?}: {:?}", terminator, errors));
+
+        // the line comment ends at the terminator too
+        let newlines: Vec<bool> = tokens.iter().map(|t| t.newline_before).collect();
+        assert_eq!(newlines, vec![false, true, true, false], "{:?}", terminator);
+        let lines: Vec<(usize, usize)> = tokens.iter().map(|t| (t.span.line, t.span.column)).collect();
+        assert_eq!(lines[..3], [(1, 1), (2, 1), (3, 1)], "{:?}", terminator);
+    }
+
+    // a line break inside a block comment
+    let mut tokenizer = Tokenizer::new("a /*\u{2028}*/ b".into());
+    let newlines: Vec<bool> = tokenizer.tokenize().unwrap().iter().map(|t| t.newline_before).collect();
+    assert_eq!(newlines, vec![false, true, false]);
+}
+
+#[test]
+fn test_assignment_operators() {
+    assert_eq!(
+        get_token_types("= += -= *= /= %= **= <<= >>= >>>= &= |= ^= ** << >> >>> % & | ^ >= <"),
+        vec![
+            TokenType::Assign,
+            TokenType::PlusAssign,
+            TokenType::MinusAssign,
+            TokenType::StarAssign,
+            TokenType::SlashAssign,
+            TokenType::PercentAssign,
+            TokenType::StarStarAssign,
+            TokenType::LeftShiftAssign,
+            TokenType::RightShiftAssign,
+            TokenType::UnsignedRightShiftAssign,
+            TokenType::AmpersandAssign,
+            TokenType::PipeAssign,
+            TokenType::CaretAssign,
+            TokenType::StarStar,
+            TokenType::LeftShift,
+            TokenType::RightShift,
+            TokenType::UnsignedRightShift,
+            TokenType::Percent,
+            TokenType::Ampersand,
+            TokenType::Pipe,
+            TokenType::Caret,
+            TokenType::GreaterThanOrEqual,
+            TokenType::LessThan,
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_dot_and_leading_dot_numbers() {
+    assert_eq!(
+        get_token_types("e.message .5"),
+        vec![
+            TokenType::Identifier("e".into()),
+            TokenType::Dot,
+            TokenType::Identifier("message".into()),
+            TokenType::Literal(Literal::Number(0.5)),
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_logical_operators() {
+    assert_eq!(
+        get_token_types("!a && b || c ?? d &&= e ||= f ??= g != h"),
+        vec![
+            TokenType::Bang,
+            TokenType::Identifier("a".into()),
+            TokenType::AmpersandAmpersand,
+            TokenType::Identifier("b".into()),
+            TokenType::PipePipe,
+            TokenType::Identifier("c".into()),
+            TokenType::QuestionQuestion,
+            TokenType::Identifier("d".into()),
+            TokenType::AmpersandAmpersandAssign,
+            TokenType::Identifier("e".into()),
+            TokenType::PipePipeAssign,
+            TokenType::Identifier("f".into()),
+            TokenType::QuestionQuestionAssign,
+            TokenType::Identifier("g".into()),
+            TokenType::NotEqual,
+            TokenType::Identifier("h".into()),
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_unary_and_update_operators() {
+    assert_eq!(
+        get_token_types("typeof void delete ~+a++ - --b"),
+        vec![
+            TokenType::Typeof,
+            TokenType::Void,
+            TokenType::Delete,
+            TokenType::Tilde,
+            TokenType::Plus,
+            TokenType::Identifier("a".into()),
+            TokenType::PlusPlus,
+            TokenType::Minus,
+            TokenType::MinusMinus,
+            TokenType::Identifier("b".into()),
+            TokenType::Eof,
+        ]
+    );
+}